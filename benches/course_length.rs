@@ -0,0 +1,107 @@
+use std::hint::black_box;
+
+use bevy_ecs::{
+    message::{MessageRegistry, Messages},
+    schedule::Schedule,
+    system::{Query, Res},
+    world::World,
+};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use flipper::{
+    collision::{Collider, CollisionEnded, CollisionStarted, Contacts, detect_collisions},
+    components::{ClearedBy, HorizontalExtent, ObstacleShape, Placed, Position},
+    controls::Controls,
+    display::VIRTUAL_HEIGHT,
+    resources::{Camera, CourseIndex},
+    systems::{flipper_bundle, local_player},
+    theme::Theme,
+};
+
+/// World at the start of a run along a course of `obstacle_count` obstacles, one every 400
+/// pixels, indexed as a level spawns them.
+fn course_world(obstacle_count: usize) -> World {
+    let mut world = World::new();
+    world.init_resource::<Camera>();
+    world.init_resource::<Contacts>();
+    MessageRegistry::register_message::<CollisionStarted>(&mut world);
+    MessageRegistry::register_message::<CollisionEnded>(&mut world);
+    let [colour, _] = Theme::default().flippers;
+    world.spawn(flipper_bundle(
+        local_player(1, &Controls::default()),
+        colour,
+    ));
+    let mut course_index = CourseIndex::default();
+    for index in 1..=obstacle_count {
+        #[allow(clippy::cast_precision_loss)]
+        let position = Position {
+            x: 400.0 * index as f32,
+            y: 0.0,
+        };
+        let shape = ObstacleShape {
+            gap_length: 200.0,
+            gap_y_displacement: 150.0,
+            width: 15.0,
+        };
+        let (left, right) = shape.horizontal_extent(&position);
+        let obstacle = world
+            .spawn((
+                position,
+                shape,
+                ClearedBy::default(),
+                Collider::rectangle(15.0, VIRTUAL_HEIGHT),
+                Placed,
+            ))
+            .id();
+        course_index.insert(obstacle, left, right);
+    }
+    world.insert_resource(course_index);
+
+    world
+}
+
+/// The lookup `draw_obstacles` makes each frame, without the drawing, which needs a window.
+#[allow(clippy::needless_pass_by_value)]
+fn find_visible_obstacles(
+    query: Query<(&Position, &ObstacleShape)>,
+    camera: Res<Camera>,
+    course_index: Res<CourseIndex>,
+) {
+    for (position, shape) in query.iter_many(course_index.near_view(&camera)) {
+        black_box(camera.in_view(shape, position));
+    }
+}
+
+/// Per-frame cost of the broad phase should stay flat however long the course.
+fn broad_phase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broad_phase");
+    for obstacle_count in [10, 1_000, 10_000] {
+        let mut world = course_world(obstacle_count);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(detect_collisions);
+        group.bench_function(BenchmarkId::from_parameter(obstacle_count), |b| {
+            b.iter(|| {
+                schedule.run(&mut world);
+                world.resource_mut::<Messages<CollisionStarted>>().update();
+                world.resource_mut::<Messages<CollisionEnded>>().update();
+            });
+        });
+    }
+    group.finish();
+}
+
+/// Per-frame cost of finding the obstacles to draw should stay flat however long the course.
+fn draw_culling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("draw_culling");
+    for obstacle_count in [10, 1_000, 10_000] {
+        let mut world = course_world(obstacle_count);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(find_visible_obstacles);
+        group.bench_function(BenchmarkId::from_parameter(obstacle_count), |b| {
+            b.iter(|| schedule.run(&mut world));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, broad_phase, draw_culling);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+use bevy_ecs::{
+    entity::Entity,
+    message::{MessageRegistry, Messages},
+    schedule::Schedule,
+    world::World,
+};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use flipper::{
+    collision::{CollisionPair, CollisionStarted},
+    components::{ClearedBy, ObstacleShape, Position},
+    controls::Controls,
+    resources::GameAssets,
+    systems::{FlipperHit, flipper_bundle, handle_obstacle_flipper_collision, local_player},
+    theme::Theme,
+};
+
+/// World with a flipper about to cross an obstacle, after `cleared_count` obstacles already
+/// cleared earlier in the run, and the collision judging that crossing.
+fn obstacle_crossing_world(cleared_count: usize) -> (World, Schedule, CollisionPair) {
+    let mut world = World::new();
+    world.init_resource::<GameAssets>();
+    MessageRegistry::register_message::<CollisionStarted>(&mut world);
+    MessageRegistry::register_message::<FlipperHit>(&mut world);
+    let player_one = local_player(1, &Controls::default());
+    let [colour, _] = Theme::default().flippers;
+    let flipper = world.spawn(flipper_bundle(player_one, colour)).id();
+    let obstacles: Vec<Entity> = (0..=cleared_count)
+        .map(|index| {
+            let mut cleared_by = ClearedBy::default();
+            if index < cleared_count {
+                cleared_by.insert(player_one);
+            }
+            #[allow(clippy::cast_precision_loss)]
+            let x = 400.0 * index as f32;
+            world
+                .spawn((
+                    Position { x, y: 0.0 },
+                    ObstacleShape {
+                        gap_length: 200.0,
+                        gap_y_displacement: 150.0,
+                        width: 15.0,
+                    },
+                    cleared_by,
+                ))
+                .id()
+        })
+        .collect();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(handle_obstacle_flipper_collision);
+    let pair = CollisionPair::new(flipper, obstacles[cleared_count]);
+
+    (world, schedule, pair)
+}
+
+/// Per-frame cost of judging a crossing should stay flat as cleared obstacles pile up.
+fn obstacle_crossing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("obstacle_crossing");
+    for cleared_count in [10, 1_000, 10_000] {
+        let (mut world, mut schedule, pair) = obstacle_crossing_world(cleared_count);
+        group.bench_function(BenchmarkId::from_parameter(cleared_count), |b| {
+            b.iter(|| {
+                world.write_message(CollisionStarted { pair, time: 1.0 });
+                schedule.run(&mut world);
+                world.resource_mut::<Messages<CollisionStarted>>().update();
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, obstacle_crossing);
+criterion_main!(benches);
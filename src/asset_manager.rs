@@ -17,6 +17,7 @@ const BACKGROUND_SOUND_PATH: &str = "./assets/background.wav";
 const FLAP_SOUND_PATH: &str = "./assets/flap.wav";
 const GAME_OVER_SOUND_PATH: &str = "./assets/game_over.wav";
 const OBSTACLE_CLEARED_SOUND_PATH: &str = "./assets/obstacle_cleared.wav";
+const PICKUP_SOUND_PATH: &str = "./assets/pickup.wav";
 const VICTORY_SOUND_PATH: &str = "./assets/victory.wav";
 
 impl AssetManager {
@@ -40,9 +41,16 @@ impl AssetManager {
             body,
             body_italic,
             heading,
+            script: None,
+            script_path: None,
         };
     }
 
+    /// Loads the font a locale names for a script outside the shipped Latin fonts.
+    pub async fn load_script_font(path: Option<&str>) -> Option<Font> {
+        Self::load_font(path?, "script font").await
+    }
+
     async fn load_sound_asset(path: &str, description: &str) -> Option<Sound> {
         if let Ok(value) = load_sound(path).await {
             Some(value)
@@ -53,11 +61,12 @@ impl AssetManager {
     }
 
     pub async fn load_sounds(sounds: &mut GameSounds) {
-        let (background, flap, game_over, obstacle_cleared, victory) = futures::join!(
+        let (background, flap, game_over, obstacle_cleared, pickup, victory) = futures::join!(
             Self::load_sound_asset(BACKGROUND_SOUND_PATH, "background music sound"),
             Self::load_sound_asset(FLAP_SOUND_PATH, "flap sound"),
             Self::load_sound_asset(GAME_OVER_SOUND_PATH, "game over sound"),
             Self::load_sound_asset(OBSTACLE_CLEARED_SOUND_PATH, "obstacle cleared sound"),
+            Self::load_sound_asset(PICKUP_SOUND_PATH, "pickup sound"),
             Self::load_sound_asset(VICTORY_SOUND_PATH, "victory sound")
         );
         *sounds = GameSounds {
@@ -65,6 +74,7 @@ impl AssetManager {
             flap,
             game_over,
             obstacle_cleared,
+            pickup,
             victory,
         };
     }
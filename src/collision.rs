@@ -0,0 +1,455 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    message::{Message, MessageWriter},
+    query::{With, Without},
+    resource::Resource,
+    system::{Query, Res, ResMut},
+};
+
+use crate::{
+    components::{Hitbox, HorizontalExtent, Placed, Position, PreviousPosition, RectangleShape},
+    resources::{Camera, CourseIndex},
+};
+
+/// Axis-aligned box, offset from the owning entity's `Position`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Aabb {
+    pub x_offset: f32,
+    pub y_offset: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Aabb {
+    #[must_use]
+    pub fn left(&self, position: &Position) -> f32 {
+        position.x + self.x_offset
+    }
+
+    #[must_use]
+    pub fn right(&self, position: &Position) -> f32 {
+        position.x + self.x_offset + self.width
+    }
+
+    #[must_use]
+    pub fn top(&self, position: &Position) -> f32 {
+        position.y + self.y_offset
+    }
+
+    #[must_use]
+    pub fn bottom(&self, position: &Position) -> f32 {
+        position.y + self.y_offset + self.height
+    }
+
+    /// True when the boxes share some interior; touching edges do not count.
+    #[must_use]
+    pub fn overlaps(&self, position: &Position, other: &Aabb, other_position: &Position) -> bool {
+        self.right(position) > other.left(other_position)
+            && self.left(position) < other.right(other_position)
+            && self.bottom(position) > other.top(other_position)
+            && self.top(position) < other.bottom(other_position)
+    }
+
+    /// Sweeps both boxes over the frame's motion, from their previous to current positions.
+    /// Returns the fraction of the frame, in 0 to 1, at which they first touch, or `None` if they
+    /// never overlap.  Sampling there, rather than at the end of the frame, means a thin box is
+    /// still hit when a long frame carries the other right past it.
+    #[must_use]
+    pub fn sweep(
+        &self,
+        previous: &Position,
+        position: &Position,
+        other: &Aabb,
+        other_previous: &Position,
+        other_position: &Position,
+    ) -> Option<f32> {
+        // only motion relative to `other` matters
+        let x_displacement = (position.x - previous.x) - (other_position.x - other_previous.x);
+        let y_displacement = (position.y - previous.y) - (other_position.y - other_previous.y);
+
+        if x_displacement.abs() < f32::EPSILON && y_displacement.abs() < f32::EPSILON {
+            return self
+                .overlaps(position, other, other_position)
+                .then_some(1.0);
+        }
+
+        let (x_enter, x_exit) = sweep_axis(
+            (self.left(previous), self.right(previous)),
+            (other.left(other_previous), other.right(other_previous)),
+            x_displacement,
+        )?;
+        let (y_enter, y_exit) = sweep_axis(
+            (self.top(previous), self.bottom(previous)),
+            (other.top(other_previous), other.bottom(other_previous)),
+            y_displacement,
+        )?;
+        let enter = x_enter.max(y_enter).max(0.0);
+        let exit = x_exit.min(y_exit).min(1.0);
+
+        (enter < exit).then_some(enter)
+    }
+}
+
+/// Interval of time, in units of `displacement`, during which the moving span overlaps the
+/// static one, or `None` if it never does.
+fn sweep_axis(
+    (min, max): (f32, f32),
+    (other_min, other_max): (f32, f32),
+    displacement: f32,
+) -> Option<(f32, f32)> {
+    if displacement.abs() < f32::EPSILON {
+        return (max > other_min && min < other_max).then_some((f32::NEG_INFINITY, f32::INFINITY));
+    }
+    let enter = (other_min - max) / displacement;
+    let exit = (other_max - min) / displacement;
+
+    Some((enter.min(exit), enter.max(exit)))
+}
+
+#[derive(Clone, Component, Copy, Debug, PartialEq)]
+pub struct Collider {
+    pub aabb: Aabb,
+}
+
+impl Collider {
+    /// Collider matching a rectangle with its top-left corner at the entity `Position`.
+    #[must_use]
+    pub fn rectangle(width: f32, height: f32) -> Self {
+        Collider {
+            aabb: Aabb {
+                x_offset: 0.0,
+                y_offset: 0.0,
+                width,
+                height,
+            },
+        }
+    }
+
+    /// Collider for a rectangle sprite, shrunk by the hitbox inset on every side.
+    #[must_use]
+    pub fn hitbox(shape: &RectangleShape, hitbox: &Hitbox) -> Self {
+        let Hitbox { inset } = *hitbox;
+        Collider {
+            aabb: Aabb {
+                x_offset: inset,
+                y_offset: inset,
+                width: shape.width - 2.0 * inset,
+                height: shape.height - 2.0 * inset,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn sweep(
+        &self,
+        previous: &Position,
+        position: &Position,
+        other: &Collider,
+        other_previous: &Position,
+        other_position: &Position,
+    ) -> Option<f32> {
+        self.aabb.sweep(
+            previous,
+            position,
+            &other.aabb,
+            other_previous,
+            other_position,
+        )
+    }
+}
+
+impl HorizontalExtent for Collider {
+    fn horizontal_extent(&self, position: &Position) -> (f32, f32) {
+        (self.aabb.left(position), self.aabb.right(position))
+    }
+}
+
+/// Pair of entities with overlapping colliders, stored with the lower `Entity` first.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CollisionPair(Entity, Entity);
+
+impl CollisionPair {
+    #[must_use]
+    pub fn new(first: Entity, second: Entity) -> Self {
+        if first < second {
+            CollisionPair(first, second)
+        } else {
+            CollisionPair(second, first)
+        }
+    }
+
+    /// The entity paired with `entity`, or `None` if `entity` is not part of the pair.
+    #[must_use]
+    pub fn other(&self, entity: Entity) -> Option<Entity> {
+        let CollisionPair(first, second) = *self;
+        if first == entity {
+            Some(second)
+        } else if second == entity {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+/// Written on the first frame two colliders overlap.
+#[derive(Debug, Message)]
+pub struct CollisionStarted {
+    pub pair: CollisionPair,
+
+    /// Fraction of the frame's motion at which the contact was sampled, for interpolating
+    /// positions from `PreviousPosition`.
+    pub time: f32,
+}
+
+/// Written on the first frame two previously-overlapping colliders no longer overlap.
+#[derive(Debug, Message)]
+pub struct CollisionEnded {
+    pub pair: CollisionPair,
+}
+
+/// Pairs which overlapped at some point during the last collision pass.
+#[derive(Debug, Default, Resource)]
+pub struct Contacts {
+    pub pairs: HashSet<CollisionPair>,
+}
+
+/// Splits the overlaps found this frame into pairs which just started and pairs which just ended,
+/// relative to the previous frame's contacts.
+fn diff_contacts(
+    previous: &HashSet<CollisionPair>,
+    current: &HashMap<CollisionPair, f32>,
+) -> (Vec<(CollisionPair, f32)>, Vec<CollisionPair>) {
+    let started = current
+        .iter()
+        .filter(|(pair, _)| !previous.contains(pair))
+        .map(|(pair, time)| (*pair, *time))
+        .collect();
+    let ended = previous
+        .iter()
+        .filter(|pair| !current.contains_key(pair))
+        .copied()
+        .collect();
+
+    (started, ended)
+}
+
+/// Copies each `Position` into its `PreviousPosition`; runs before anything moves this frame.
+pub fn store_previous_positions(mut query: Query<(&Position, &mut PreviousPosition)>) {
+    for (position, mut previous) in &mut query {
+        previous.0 = position.clone();
+    }
+}
+
+/// Broad-phase pass: only colliders near the camera are tested, with those placed along the
+/// course found through the `CourseIndex` rather than by visiting all of them, then each
+/// overlapping pair is
+/// compared with the last frame to emit start and end messages.  Entities without a
+/// `PreviousPosition` are treated as stationary over the frame.
+#[allow(clippy::needless_pass_by_value)]
+pub fn detect_collisions(
+    query: Query<(Entity, &Position, Option<&PreviousPosition>, &Collider)>,
+    unplaced_query: Query<Entity, (With<Collider>, Without<Placed>)>,
+    camera: Res<Camera>,
+    course_index: Res<CourseIndex>,
+    mut contacts: ResMut<Contacts>,
+    mut started_writer: MessageWriter<CollisionStarted>,
+    mut ended_writer: MessageWriter<CollisionEnded>,
+) {
+    let candidates = unplaced_query.iter().chain(course_index.near_view(&camera));
+    let nearby: Vec<(Entity, &Position, &Position, &Collider)> = query
+        .iter_many(candidates)
+        .filter(|(_, position, _, collider)| camera.in_view(*collider, position))
+        .map(|(entity, position, previous, collider)| {
+            (
+                entity,
+                previous.map_or(position, |PreviousPosition(value)| value),
+                position,
+                collider,
+            )
+        })
+        .collect();
+
+    let mut current = HashMap::new();
+    for (index, (entity, previous, position, collider)) in nearby.iter().enumerate() {
+        for (other_entity, other_previous, other_position, other_collider) in &nearby[index + 1..] {
+            if let Some(time) = collider.sweep(
+                previous,
+                position,
+                other_collider,
+                other_previous,
+                other_position,
+            ) {
+                current.insert(CollisionPair::new(*entity, *other_entity), time);
+            }
+        }
+    }
+
+    let (started, ended) = diff_contacts(&contacts.pairs, &current);
+    for (pair, time) in started {
+        started_writer.write(CollisionStarted { pair, time });
+    }
+    for pair in ended {
+        ended_writer.write(CollisionEnded { pair });
+    }
+    contacts.pairs = current.into_keys().collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use bevy_ecs::entity::Entity;
+    use float_cmp::approx_eq;
+
+    use super::{Aabb, Collider, CollisionPair, diff_contacts};
+    use crate::components::Position;
+
+    #[test]
+    fn aabb_overlaps_successfully_reports_overlap() {
+        // arrange
+        let Collider { aabb } = Collider::rectangle(20.0, 20.0);
+        let position = Position { x: 100.0, y: 120.0 };
+        let Collider { aabb: other_aabb } = Collider::rectangle(15.0, 600.0);
+        let other_position = Position { x: 110.0, y: 0.0 };
+
+        // act
+        let result = aabb.overlaps(&position, &other_aabb, &other_position);
+
+        // assert
+        assert!(result);
+    }
+
+    #[test]
+    fn aabb_overlaps_ignores_touching_edges() {
+        // arrange
+        let Collider { aabb } = Collider::rectangle(20.0, 20.0);
+        let position = Position { x: 100.0, y: 120.0 };
+        let Collider { aabb: other_aabb } = Collider::rectangle(15.0, 600.0);
+        let other_position = Position { x: 120.0, y: 0.0 };
+
+        // act
+        let result = aabb.overlaps(&position, &other_aabb, &other_position);
+
+        // assert
+        assert!(!result);
+    }
+
+    #[test]
+    fn collider_sweep_detects_thin_collider_passed_in_one_frame() {
+        // arrange
+        let collider = Collider::rectangle(20.0, 20.0);
+        let previous = Position { x: 60.0, y: 300.0 };
+        let position = Position { x: 140.0, y: 300.0 };
+        let other_collider = Collider::rectangle(15.0, 600.0);
+        let other_position = Position { x: 100.0, y: 0.0 };
+
+        // act
+        let result = collider.sweep(
+            &previous,
+            &position,
+            &other_collider,
+            &other_position,
+            &other_position,
+        );
+
+        // assert
+        let Some(time) = result else {
+            panic!("Expected sweep to report a contact");
+        };
+        // overlap runs from x = 80 to x = 115, so starts a quarter of the way through the motion
+        assert!(approx_eq!(f32, time, 0.25, ulps = 2));
+    }
+
+    #[test]
+    fn collider_sweep_reports_miss_for_vertical_separation() {
+        // arrange
+        let collider = Collider::rectangle(20.0, 20.0);
+        let previous = Position { x: 60.0, y: 300.0 };
+        let position = Position { x: 140.0, y: 310.0 };
+        let other_collider = Collider {
+            aabb: Aabb {
+                x_offset: 0.0,
+                y_offset: 0.0,
+                width: 15.0,
+                height: 250.0,
+            },
+        };
+        let other_position = Position { x: 100.0, y: 0.0 };
+
+        // act
+        let result = collider.sweep(
+            &previous,
+            &position,
+            &other_collider,
+            &other_position,
+            &other_position,
+        );
+
+        // assert
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn aabb_overlaps_applies_offsets() {
+        // arrange
+        let aabb = Aabb {
+            x_offset: 0.0,
+            y_offset: 0.0,
+            width: 20.0,
+            height: 20.0,
+        };
+        let position = Position { x: 100.0, y: 120.0 };
+        let other_aabb = Aabb {
+            x_offset: 0.0,
+            y_offset: 200.0,
+            width: 15.0,
+            height: 100.0,
+        };
+        let other_position = Position { x: 110.0, y: 0.0 };
+
+        // act
+        let result = aabb.overlaps(&position, &other_aabb, &other_position);
+
+        // assert
+        assert!(!result);
+    }
+
+    #[test]
+    fn collision_pair_other_returns_expected_value() {
+        // arrange
+        let first = Entity::from_raw_u32(1).unwrap();
+        let second = Entity::from_raw_u32(2).unwrap();
+        let third = Entity::from_raw_u32(3).unwrap();
+        let pair = CollisionPair::new(second, first);
+
+        // act
+        let result = (pair.other(first), pair.other(second), pair.other(third));
+
+        // assert
+        assert_eq!(result, (Some(second), Some(first), None));
+    }
+
+    #[test]
+    fn diff_contacts_reports_started_and_ended_pairs() {
+        // arrange
+        let first = Entity::from_raw_u32(1).unwrap();
+        let second = Entity::from_raw_u32(2).unwrap();
+        let third = Entity::from_raw_u32(3).unwrap();
+        let ongoing = CollisionPair::new(first, second);
+        let finished = CollisionPair::new(first, third);
+        let new = CollisionPair::new(second, third);
+        let previous = HashSet::from([ongoing, finished]);
+        let current = HashMap::from([(ongoing, 1.0), (new, 0.5)]);
+
+        // act
+        let (started, ended) = diff_contacts(&previous, &current);
+
+        // assert
+        assert_eq!(started, vec![(new, 0.5)]);
+        assert_eq!(ended, vec![finished]);
+    }
+}
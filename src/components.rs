@@ -1,6 +1,7 @@
-use bevy_ecs::component::Component;
+use bevy_ecs::component::{Component, Mutable};
+use macroquad::input::KeyCode;
 
-#[derive(Clone, Component, Copy, Debug, Default)]
+#[derive(Clone, Component, Copy, Debug, Default, PartialEq)]
 pub struct Colour {
     pub r: u8,
     pub g: u8,
@@ -21,6 +22,7 @@ impl From<Colour> for macroquad::color::Color {
 }
 
 impl Colour {
+    #[must_use]
     pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
         Colour { r, g, b, a }
     }
@@ -32,18 +34,124 @@ pub struct FinishLine;
 #[derive(Component, Debug, Default)]
 pub struct Flipper {}
 
-#[derive(Component, Debug, Default)]
+/// Local player a flipper belongs to, numbered from one, and the key it flaps with.
+#[derive(Clone, Component, Copy, Debug)]
+pub struct Player {
+    pub number: u8,
+    pub flap_key: KeyCode,
+}
+
+/// Marks a flipper which is out of the current run.  In a two-player race the other player
+/// carries on until they are out too, or cross the finish line.
+#[derive(Component, Debug)]
+pub struct Eliminated;
+
+/// Players who have already cleared, or shielded through, an obstacle, so it counts only once
+/// for each.  Kept on the obstacle itself, so the state goes when the obstacle despawns and
+/// checking it costs the same however long the course.
+#[derive(Clone, Component, Copy, Debug, Default)]
+pub struct ClearedBy {
+    /// One bit per player, from player one in the lowest bit.
+    players: u8,
+}
+
+impl ClearedBy {
+    /// The player's bit, or `None` for numbers outside one to eight, which never count as
+    /// cleared.
+    fn bit(player: Player) -> Option<u8> {
+        1u8.checked_shl(u32::from(player.number.checked_sub(1)?))
+    }
+
+    #[must_use]
+    pub fn contains(self, player: Player) -> bool {
+        ClearedBy::bit(player).is_some_and(|bit| self.players & bit != 0)
+    }
+
+    pub fn insert(&mut self, player: Player) {
+        if let Some(bit) = ClearedBy::bit(player) {
+            self.players |= bit;
+        }
+    }
+
+    pub fn remove(&mut self, player: Player) {
+        if let Some(bit) = ClearedBy::bit(player) {
+            self.players &= !bit;
+        }
+    }
+}
+
+/// Marks everything belonging to a single run: the flippers, obstacles, checkpoints, finish line
+/// and pickups.  Restarting a run despawns the lot and spawns it afresh.
+#[derive(Component, Debug)]
+pub struct RunEntity;
+
+/// Marks an entity laid out along the course, which systems find through the `CourseIndex`
+/// rather than by visiting every entity in the run.
+#[derive(Component, Debug)]
+pub struct Placed;
+
+/// Vertical line which, once passed, becomes the flipper respawn point.
+#[derive(Component, Debug)]
+pub struct Checkpoint;
+
+/// Lives left in the current run.  The flipper only carries this when lives are enabled.
+#[derive(Component, Debug)]
+pub struct Lives {
+    pub remaining: u32,
+}
+
+impl Lives {
+    /// Takes a life, returning `false` once there were none to spare, which ends the run.
+    pub fn lose_life(&mut self) -> bool {
+        if self.remaining > 1 {
+            self.remaining -= 1;
+            true
+        } else {
+            self.remaining = 0;
+            false
+        }
+    }
+}
+
+/// Horizontal position the flipper returns to after losing a life.
+#[derive(Component, Debug)]
+pub struct RespawnPoint {
+    pub x: f32,
+}
+
+#[derive(Clone, Component, Debug, Default)]
 pub struct Position {
     pub x: f32,
     pub y: f32,
 }
 
+impl Position {
+    /// Position a fraction `time` of the way from `previous` to this position.
+    #[must_use]
+    pub fn interpolate_from(&self, previous: &Position, time: f32) -> Position {
+        Position {
+            x: previous.x + time * (self.x - previous.x),
+            y: previous.y + time * (self.y - previous.y),
+        }
+    }
+}
+
+/// Position at the start of the current frame, so collisions can be swept over the frame's
+/// motion.
+#[derive(Component, Debug, Default)]
+pub struct PreviousPosition(pub Position);
+
 #[derive(Component, Debug, Default)]
 pub struct Velocity {
     pub x: f32,
     pub y: f32,
 }
 
+/// Horizontal span an entity covers in world units, shared by anything deciding visibility.
+pub trait HorizontalExtent {
+    fn horizontal_extent(&self, position: &Position) -> (f32, f32);
+}
+
 #[derive(Component, Debug, Default)]
 pub struct RectangleShape {
     pub width: f32,
@@ -52,24 +160,41 @@ pub struct RectangleShape {
 
 impl RectangleShape {
     #[allow(clippy::unused_self)]
+    #[must_use]
     pub fn left(&self, position: &Position) -> f32 {
         position.x
     }
 
+    #[must_use]
     pub fn right(&self, position: &Position) -> f32 {
         position.x + self.width
     }
 
     #[allow(clippy::unused_self)]
+    #[must_use]
     pub fn top(&self, position: &Position) -> f32 {
         position.y
     }
 
+    #[must_use]
     pub fn bottom(&self, position: &Position) -> f32 {
         position.y + self.height
     }
 }
 
+impl HorizontalExtent for RectangleShape {
+    fn horizontal_extent(&self, position: &Position) -> (f32, f32) {
+        (self.left(position), self.right(position))
+    }
+}
+
+/// Distance the collision box sits inside each edge of the visible `RectangleShape`, so grazing
+/// an obstacle by a pixel or two is forgiven.
+#[derive(Component, Debug, Default)]
+pub struct Hitbox {
+    pub inset: f32,
+}
+
 #[derive(Component, Debug, Default)]
 pub struct ObstacleShape {
     pub gap_length: f32,
@@ -79,32 +204,392 @@ pub struct ObstacleShape {
 
 impl ObstacleShape {
     #[allow(clippy::unused_self)]
+    #[must_use]
     pub fn left(&self, position: &Position) -> f32 {
         position.x
     }
 
+    #[must_use]
     pub fn right(&self, position: &Position) -> f32 {
         position.x + self.width
     }
 
+    #[must_use]
     pub fn gap_top(&self) -> f32 {
         self.gap_y_displacement
     }
 
+    #[must_use]
     pub fn gap_bottom(&self) -> f32 {
         self.gap_y_displacement + self.gap_length
     }
 }
 
+impl HorizontalExtent for ObstacleShape {
+    fn horizontal_extent(&self, position: &Position) -> (f32, f32) {
+        (self.left(position), self.right(position))
+    }
+}
+
+/// Offset from an obstacle's resting value as a function of time, in seconds.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MotionPath {
+    /// Smooth bob between `-amplitude` and `amplitude`, starting from rest.
+    Sine { amplitude: f32, period: f32 },
+
+    /// Constant speed back and forth between `-amplitude` and `amplitude`, starting from rest.
+    PingPong { amplitude: f32, period: f32 },
+
+    /// Linear interpolation between keyframes, looping after the last one.
+    Keyframes(Vec<Keyframe>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub offset: f32,
+}
+
+impl MotionPath {
+    /// Why the path cannot be followed, if it cannot: a period that is not positive gives no
+    /// offset at all, and keyframes out of time order skip between each other.
+    #[must_use]
+    pub fn problem(&self) -> Option<&'static str> {
+        match self {
+            MotionPath::Sine { period, .. } | MotionPath::PingPong { period, .. } => {
+                (!period.is_finite() || *period <= 0.0).then_some("its period is not positive")
+            }
+            MotionPath::Keyframes(keyframes) => (!keyframes
+                .is_sorted_by(|earlier, later| earlier.time <= later.time))
+            .then_some("its keyframes are out of time order"),
+        }
+    }
+
+    /// Lowest and highest offsets the path reaches.
+    #[must_use]
+    pub fn range(&self) -> (f32, f32) {
+        match self {
+            MotionPath::Sine { amplitude, .. } | MotionPath::PingPong { amplitude, .. } => {
+                (-amplitude.abs(), amplitude.abs())
+            }
+            MotionPath::Keyframes(keyframes) => {
+                keyframes.iter().fold((0.0, 0.0), |(low, high), keyframe| {
+                    (low.min(keyframe.offset), high.max(keyframe.offset))
+                })
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn offset(&self, time: f32) -> f32 {
+        match self {
+            MotionPath::Sine { amplitude, period } => {
+                amplitude * (std::f32::consts::TAU * time / period).sin()
+            }
+            MotionPath::PingPong { amplitude, period } => {
+                // quarter cycle phase shift, so the path starts at zero offset, heading up
+                let phase = (time / period + 0.25).fract();
+                amplitude * (1.0 - 4.0 * (phase - 0.5).abs())
+            }
+            MotionPath::Keyframes(keyframes) => {
+                let (Some(first), Some(last)) = (keyframes.first(), keyframes.last()) else {
+                    return 0.0;
+                };
+                if last.time <= 0.0 {
+                    return first.offset;
+                }
+                let looped_time = time.rem_euclid(last.time);
+                let mut previous = first;
+                for keyframe in keyframes {
+                    if keyframe.time >= looped_time {
+                        let span = keyframe.time - previous.time;
+                        if span <= 0.0 {
+                            return keyframe.offset;
+                        }
+                        let fraction = (looped_time - previous.time) / span;
+                        return previous.offset + fraction * (keyframe.offset - previous.offset);
+                    }
+                    previous = keyframe;
+                }
+                last.offset
+            }
+        }
+    }
+}
+
+/// Paths for each obstacle property which can move; `None` leaves the property at rest.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MotionPaths {
+    pub x: Option<MotionPath>,
+    pub gap_y: Option<MotionPath>,
+
+    /// Changes the gap length about the gap centre, so the gap narrows and widens in place.
+    pub gap_length: Option<MotionPath>,
+}
+
+/// Scripted motion for an obstacle, remembering the resting values the paths are relative to.
+#[derive(Component, Debug)]
+pub struct ObstacleMotion {
+    pub paths: MotionPaths,
+    pub elapsed: f32,
+    rest_x: f32,
+    rest_gap_length: f32,
+    rest_gap_y_displacement: f32,
+}
+
+impl ObstacleMotion {
+    #[must_use]
+    pub fn new(paths: MotionPaths, position: &Position, shape: &ObstacleShape) -> Self {
+        ObstacleMotion {
+            paths,
+            elapsed: 0.0,
+            rest_x: position.x,
+            rest_gap_length: shape.gap_length,
+            rest_gap_y_displacement: shape.gap_y_displacement,
+        }
+    }
+
+    /// Sets the obstacle position and shape for the current `elapsed` time.
+    pub fn apply(&self, position: &mut Position, shape: &mut ObstacleShape) {
+        let MotionPaths {
+            x,
+            gap_y,
+            gap_length,
+        } = &self.paths;
+        let offset = |path: &Option<MotionPath>| {
+            path.as_ref()
+                .map_or(0.0, |value| value.offset(self.elapsed))
+        };
+
+        let gap_length_offset = offset(gap_length).max(-self.rest_gap_length);
+        position.x = self.rest_x + offset(x);
+        shape.gap_length = self.rest_gap_length + gap_length_offset;
+        shape.gap_y_displacement =
+            self.rest_gap_y_displacement + offset(gap_y) - 0.5 * gap_length_offset;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CollectibleKind {
+    Pearl,
+    Shell,
+}
+
+impl CollectibleKind {
+    #[must_use]
+    pub fn default_value(self) -> u32 {
+        match self {
+            CollectibleKind::Pearl => 5,
+            CollectibleKind::Shell => 2,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Collectible {
+    pub kind: CollectibleKind,
+
+    /// Bonus added to the score on pickup.
+    pub value: u32,
+}
+
+/// Grow-and-fade played where a collectible was picked up, before the entity despawns.
+#[derive(Component, Debug, Default)]
+pub struct PickupEffect {
+    pub elapsed: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerUpKind {
+    Magnet,
+    Shield,
+    SlowMotion,
+}
+
+impl PowerUpKind {
+    /// Seconds the effect lasts once collected.
+    #[must_use]
+    pub fn duration(self) -> f32 {
+        match self {
+            PowerUpKind::Magnet => 6.0,
+            PowerUpKind::Shield => 8.0,
+            PowerUpKind::SlowMotion => 5.0,
+        }
+    }
+}
+
+/// Pickup which grants a power-up effect to the flipper.
+#[derive(Component, Debug)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+}
+
+/// Effect which wears off after a number of real (not slowed) seconds.
+pub trait TimedEffect: Component<Mutability = Mutable> {
+    fn remaining_mut(&mut self) -> &mut f32;
+}
+
+/// Absorbs the next obstacle hit.
+#[derive(Component, Debug)]
+pub struct Shield {
+    pub remaining: f32,
+}
+
+impl TimedEffect for Shield {
+    fn remaining_mut(&mut self) -> &mut f32 {
+        &mut self.remaining
+    }
+}
+
+/// Slows the whole world down by `time_scale`.
+#[derive(Component, Debug)]
+pub struct SlowMotion {
+    pub remaining: f32,
+    pub time_scale: f32,
+}
+
+impl TimedEffect for SlowMotion {
+    fn remaining_mut(&mut self) -> &mut f32 {
+        &mut self.remaining
+    }
+}
+
+/// Pulls collectibles within `radius` towards the flipper at `speed`.
+#[derive(Component, Debug)]
+pub struct Magnet {
+    pub remaining: f32,
+    pub radius: f32,
+    pub speed: f32,
+}
+
+impl TimedEffect for Magnet {
+    fn remaining_mut(&mut self) -> &mut f32 {
+        &mut self.remaining
+    }
+}
+
+/// Ignores obstacle hits for a short while after a respawn.
+#[derive(Component, Debug)]
+pub struct Invulnerable {
+    pub remaining: f32,
+}
+
+impl Invulnerable {
+    /// Whether the flipper should be hidden this frame, giving a blink a few times a second.
+    #[must_use]
+    pub fn blink_hidden(&self) -> bool {
+        (self.remaining * 4.0).fract() < 0.5
+    }
+}
+
+impl TimedEffect for Invulnerable {
+    fn remaining_mut(&mut self) -> &mut f32 {
+        &mut self.remaining
+    }
+}
+
+/// Run clock and splits, in game seconds, carried by the flipper during a time trial.
+#[derive(Component, Debug, Default)]
+pub struct TimeTrial {
+    pub elapsed: f32,
+
+    /// Elapsed time as each obstacle was cleared, in order.
+    pub splits: Vec<f32>,
+}
+
+/// Lets the player boost or brake the flipper between `min_speed` and `max_speed`.
+#[derive(Component, Debug)]
+pub struct SpeedControl {
+    pub min_speed: f32,
+    pub max_speed: f32,
+    pub acceleration: f32,
+}
+
+impl SpeedControl {
+    /// Speed after `delta` seconds, where `direction` is 1 to boost, -1 to brake and 0 to
+    /// hold speed.
+    #[must_use]
+    pub fn adjust(&self, speed: f32, direction: f32, delta: f32) -> f32 {
+        (speed + direction * self.acceleration * delta).clamp(self.min_speed, self.max_speed)
+    }
+}
+
 #[derive(Component, Debug, Default)]
 pub struct Score {
+    /// Total, including collectible bonuses.
     pub value: u32,
+    pub obstacles_cleared: u32,
+    pub items_collected: u32,
+}
+
+/// Obstacles cleared in a row since the flipper was last hit.  Every `COMBO_STEP` in a row
+/// raises the points each clear is worth, up to `MAX_COMBO_MULTIPLIER`.
+#[derive(Component, Debug, Default)]
+pub struct Combo {
+    pub streak: u32,
+}
+
+const COMBO_STEP: u32 = 5;
+const MAX_COMBO_MULTIPLIER: u32 = 4;
+
+impl Combo {
+    #[must_use]
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.streak / COMBO_STEP).min(MAX_COMBO_MULTIPLIER)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ObstacleShape, Position, RectangleShape};
+    use super::{
+        ClearedBy, Combo, Keyframe, Lives, MotionPath, MotionPaths, ObstacleMotion, ObstacleShape,
+        Player, Position, RectangleShape, SpeedControl,
+    };
     use float_cmp::approx_eq;
+    use macroquad::input::KeyCode;
+
+    #[test]
+    fn cleared_by_tracks_players_separately() {
+        // arrange
+        let player_one = Player {
+            number: 1,
+            flap_key: KeyCode::Space,
+        };
+        let player_two = Player {
+            number: 2,
+            flap_key: KeyCode::Enter,
+        };
+        let mut cleared_by = ClearedBy::default();
+
+        // act
+        cleared_by.insert(player_one);
+        cleared_by.insert(player_two);
+        cleared_by.remove(player_one);
+
+        // assert
+        assert!(!cleared_by.contains(player_one));
+        assert!(cleared_by.contains(player_two));
+    }
+
+    #[test]
+    fn cleared_by_ignores_player_numbers_without_a_bit() {
+        // arrange
+        let [player_zero, player_eight, player_nine] = [0, 8, 9].map(|number| Player {
+            number,
+            flap_key: KeyCode::Space,
+        });
+        let mut cleared_by = ClearedBy::default();
+
+        // act
+        for player in [player_zero, player_eight, player_nine] {
+            cleared_by.insert(player);
+        }
+
+        // assert
+        assert!(!cleared_by.contains(player_zero));
+        assert!(cleared_by.contains(player_eight));
+        assert!(!cleared_by.contains(player_nine));
+    }
 
     #[test]
     fn obstacle_shape_left_returns_expected_value() {
@@ -194,6 +679,162 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn motion_path_sine_offset_returns_expected_values() {
+        // arrange
+        let path = MotionPath::Sine {
+            amplitude: 50.0,
+            period: 2.0,
+        };
+
+        // act
+        let result = [0.0, 0.5, 1.5].map(|time| path.offset(time));
+
+        // assert
+        assert!(approx_eq!(f32, result[0], 0.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[1], 50.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[2], -50.0, epsilon = 1e-4));
+    }
+
+    #[test]
+    fn motion_path_ping_pong_offset_returns_expected_values() {
+        // arrange
+        let path = MotionPath::PingPong {
+            amplitude: 20.0,
+            period: 4.0,
+        };
+
+        // act
+        let result = [0.0, 0.5, 1.0, 3.0, 4.0].map(|time| path.offset(time));
+
+        // assert
+        assert!(approx_eq!(f32, result[0], 0.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[1], 10.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[2], 20.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[3], -20.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[4], 0.0, epsilon = 1e-4));
+    }
+
+    #[test]
+    fn motion_path_keyframes_offset_interpolates_and_loops() {
+        // arrange
+        let path = MotionPath::Keyframes(vec![
+            Keyframe {
+                time: 0.0,
+                offset: 0.0,
+            },
+            Keyframe {
+                time: 1.0,
+                offset: 40.0,
+            },
+            Keyframe {
+                time: 2.0,
+                offset: 0.0,
+            },
+        ]);
+
+        // act
+        let result = [0.25, 1.5, 2.25].map(|time| path.offset(time));
+
+        // assert
+        assert!(approx_eq!(f32, result[0], 10.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[1], 20.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, result[2], 10.0, epsilon = 1e-4));
+    }
+
+    #[test]
+    fn motion_path_problem_reports_zero_periods_and_unsorted_keyframes() {
+        // arrange
+        let keyframes = |times: [f32; 3]| {
+            MotionPath::Keyframes(
+                times
+                    .into_iter()
+                    .map(|time| Keyframe { time, offset: 0.0 })
+                    .collect(),
+            )
+        };
+        let paths = [
+            MotionPath::Sine {
+                amplitude: 10.0,
+                period: 2.0,
+            },
+            MotionPath::Sine {
+                amplitude: 10.0,
+                period: 0.0,
+            },
+            MotionPath::PingPong {
+                amplitude: 10.0,
+                period: -1.0,
+            },
+            keyframes([0.0, 1.0, 1.0]),
+            keyframes([0.0, 2.0, 1.0]),
+        ];
+
+        // act
+        let result = paths.map(|path| path.problem().is_some());
+
+        // assert
+        assert_eq!(result, [false, true, true, false, true]);
+    }
+
+    #[test]
+    fn obstacle_motion_apply_keeps_gap_centred_while_narrowing() {
+        // arrange
+        let mut position = Position { x: 1000.0, y: 0.0 };
+        let mut shape = ObstacleShape {
+            gap_length: 100.0,
+            gap_y_displacement: 300.0,
+            width: 15.0,
+        };
+        let mut motion = ObstacleMotion::new(
+            MotionPaths {
+                gap_length: Some(MotionPath::PingPong {
+                    amplitude: 40.0,
+                    period: 4.0,
+                }),
+                ..Default::default()
+            },
+            &position,
+            &shape,
+        );
+        motion.elapsed = 3.0;
+
+        // act
+        motion.apply(&mut position, &mut shape);
+
+        // assert
+        assert!(approx_eq!(f32, shape.gap_length, 60.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, shape.gap_top(), 320.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, shape.gap_bottom(), 380.0, epsilon = 1e-4));
+        assert!(approx_eq!(f32, position.x, 1000.0, epsilon = 1e-4));
+    }
+
+    #[test]
+    fn position_interpolate_from_returns_expected_value() {
+        // arrange
+        let previous = Position { x: 100.0, y: 300.0 };
+        let position = Position { x: 140.0, y: 280.0 };
+
+        // act
+        let result = position.interpolate_from(&previous, 0.25);
+
+        // assert
+        assert!(approx_eq!(
+            f32,
+            result.x,
+            110.0,
+            epsilon = f32::EPSILON,
+            ulps = 2
+        ));
+        assert!(approx_eq!(
+            f32,
+            result.y,
+            295.0,
+            epsilon = f32::EPSILON,
+            ulps = 2
+        ));
+    }
+
     #[test]
     fn rectangle_shape_left_returns_expected_value() {
         // arrange
@@ -287,4 +928,60 @@ mod tests {
             ulps = 2
         ));
     }
+
+    #[test]
+    fn lives_lose_life_keeps_run_going_while_lives_remain() {
+        // arrange
+        let mut lives = Lives { remaining: 2 };
+
+        // act
+        let result = lives.lose_life();
+
+        // assert
+        assert!(result);
+        assert_eq!(lives.remaining, 1);
+    }
+
+    #[test]
+    fn lives_lose_life_ends_run_on_last_life() {
+        // arrange
+        let mut lives = Lives { remaining: 1 };
+
+        // act
+        let result = lives.lose_life();
+
+        // assert
+        assert!(!result);
+        assert_eq!(lives.remaining, 0);
+    }
+
+    #[test]
+    fn speed_control_adjust_clamps_to_limits() {
+        // arrange
+        let speed_control = SpeedControl {
+            min_speed: 150.0,
+            max_speed: 360.0,
+            acceleration: 240.0,
+        };
+
+        // act
+        let boosted = speed_control.adjust(350.0, 1.0, 0.1);
+        let braked = speed_control.adjust(240.0, -1.0, 0.25);
+
+        // assert
+        assert!(approx_eq!(f32, boosted, 360.0, ulps = 2));
+        assert!(approx_eq!(f32, braked, 180.0, ulps = 2));
+    }
+
+    #[test]
+    fn combo_multiplier_rises_every_step_up_to_maximum() {
+        // arrange
+        let streaks = [0, 4, 5, 14, 15, 100];
+
+        // act
+        let result = streaks.map(|streak| Combo { streak }.multiplier());
+
+        // assert
+        assert_eq!(result, [1, 1, 2, 3, 4, 4]);
+    }
 }
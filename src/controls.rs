@@ -0,0 +1,368 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy_ecs::resource::Resource;
+#[cfg(feature = "gamepad")]
+use gilrs::{EventType, Gilrs};
+use macroquad::input::{KeyCode, is_key_down, is_key_pressed, is_key_released};
+#[cfg(feature = "gamepad")]
+use macroquad::logging::error;
+
+/// Things the player can do, each bound to one or more keys, and some to gamepad buttons.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Action {
+    Flap,
+    FlapPlayerTwo,
+    Brake,
+    Boost,
+
+    /// Moves on from the title, game over and results screens.
+    Continue,
+    Confirm,
+    Back,
+    Up,
+    Down,
+    Mode,
+    Difficulty,
+    Lives,
+    Players,
+    PracticeEarlier,
+    PracticeLater,
+    PracticeSpeed,
+    Fullscreen,
+}
+
+impl Action {
+    pub const ALL: [Action; 17] = [
+        Action::Flap,
+        Action::FlapPlayerTwo,
+        Action::Brake,
+        Action::Boost,
+        Action::Continue,
+        Action::Confirm,
+        Action::Back,
+        Action::Up,
+        Action::Down,
+        Action::Mode,
+        Action::Difficulty,
+        Action::Lives,
+        Action::Players,
+        Action::PracticeEarlier,
+        Action::PracticeLater,
+        Action::PracticeSpeed,
+        Action::Fullscreen,
+    ];
+
+    /// Whether a mouse click also performs this action.
+    #[must_use]
+    pub fn takes_click(self) -> bool {
+        self == Action::Continue
+    }
+
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            Action::Flap | Action::Continue => vec![KeyCode::Space],
+            // kept clear of menu keys, so player two flapping never answers a prompt
+            Action::FlapPlayerTwo => vec![KeyCode::RightShift],
+            Action::Confirm => vec![KeyCode::Enter],
+            Action::Brake | Action::PracticeEarlier => vec![KeyCode::Left],
+            Action::Boost | Action::PracticeLater => vec![KeyCode::Right],
+            Action::Back => vec![KeyCode::Escape, KeyCode::Backspace],
+            Action::Up => vec![KeyCode::Up],
+            Action::Down => vec![KeyCode::Down],
+            Action::Mode => vec![KeyCode::M],
+            Action::Difficulty => vec![KeyCode::D],
+            Action::Lives => vec![KeyCode::L],
+            Action::Players => vec![KeyCode::P],
+            Action::PracticeSpeed => vec![KeyCode::S],
+            Action::Fullscreen => vec![KeyCode::F11],
+        }
+    }
+
+    /// Gamepad buttons for getting around the menus and screens between runs.  Flapping stays on
+    /// each player's key.
+    fn default_buttons(self) -> Vec<GamepadButton> {
+        match self {
+            Action::Continue => vec![GamepadButton::South, GamepadButton::Start],
+            Action::Confirm => vec![GamepadButton::South],
+            Action::Back => vec![GamepadButton::East],
+            Action::Up => vec![GamepadButton::DPadUp],
+            Action::Down => vec![GamepadButton::DPadDown],
+            Action::PracticeEarlier => vec![GamepadButton::DPadLeft],
+            Action::PracticeLater => vec![GamepadButton::DPadRight],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Gamepad buttons actions can be bound to, named by their place in the standard layout, so
+/// `South` is the lower face button whatever it is labelled.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    Start,
+    Select,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadButton {
+    /// The matching button, or `None` for one the game never binds.
+    fn from_gilrs(button: gilrs::Button) -> Option<Self> {
+        match button {
+            gilrs::Button::South => Some(GamepadButton::South),
+            gilrs::Button::East => Some(GamepadButton::East),
+            gilrs::Button::North => Some(GamepadButton::North),
+            gilrs::Button::West => Some(GamepadButton::West),
+            gilrs::Button::Start => Some(GamepadButton::Start),
+            gilrs::Button::Select => Some(GamepadButton::Select),
+            gilrs::Button::DPadUp => Some(GamepadButton::DPadUp),
+            gilrs::Button::DPadDown => Some(GamepadButton::DPadDown),
+            gilrs::Button::DPadLeft => Some(GamepadButton::DPadLeft),
+            gilrs::Button::DPadRight => Some(GamepadButton::DPadRight),
+            _ => None,
+        }
+    }
+}
+
+/// Change to a gamepad's buttons, from the gamepad event stream.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ButtonEvent {
+    Pressed(GamepadButton),
+    Released(GamepadButton),
+
+    /// The gamepad went away, letting go of everything it held.
+    Disconnected,
+}
+
+/// Gamepad buttons held, and those pressed or released at the last poll.
+#[derive(Debug, Default)]
+struct ButtonStates {
+    held: HashSet<GamepadButton>,
+    pressed: HashSet<GamepadButton>,
+    released: HashSet<GamepadButton>,
+}
+
+/// Device the player last used, which on-screen prompts follow.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum InputDevice {
+    #[default]
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+/// Keys and gamepad buttons bound to each action, and the device in use.  Buttons on every
+/// connected gamepad count alike.
+#[derive(Debug, Resource)]
+pub struct Controls {
+    bindings: HashMap<Action, Vec<KeyCode>>,
+    button_bindings: HashMap<Action, Vec<GamepadButton>>,
+    buttons: ButtonStates,
+    pub device: InputDevice,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Controls {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_keys()))
+                .collect(),
+            button_bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_buttons()))
+                .collect(),
+            buttons: ButtonStates::default(),
+            device: InputDevice::default(),
+        }
+    }
+}
+
+impl Controls {
+    /// Keys bound to `action`, the first being the one prompts show.
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Gamepad buttons bound to `action`, the first being the one prompts show.
+    pub fn buttons(&self, action: Action) -> &[GamepadButton] {
+        self.button_bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    fn any_button(&self, action: Action, states: &HashSet<GamepadButton>) -> bool {
+        self.buttons(action)
+            .iter()
+            .any(|button| states.contains(button))
+    }
+
+    #[must_use]
+    pub fn is_down(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|key| is_key_down(*key))
+            || self.any_button(action, &self.buttons.held)
+    }
+
+    #[must_use]
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|key| is_key_pressed(*key))
+            || self.any_button(action, &self.buttons.pressed)
+    }
+
+    #[must_use]
+    pub fn is_released(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|key| is_key_released(*key))
+            || self.any_button(action, &self.buttons.released)
+    }
+
+    /// Applies gamepad events since the last poll.  Presses and releases only count until the
+    /// next poll, like a key press lasting one frame.  A press makes the gamepad the device in
+    /// use.
+    pub fn update_buttons(&mut self, events: impl IntoIterator<Item = ButtonEvent>) {
+        let ButtonStates {
+            held,
+            pressed,
+            released,
+        } = &mut self.buttons;
+        pressed.clear();
+        released.clear();
+        for event in events {
+            match event {
+                ButtonEvent::Pressed(button) => {
+                    held.insert(button);
+                    pressed.insert(button);
+                    self.device = InputDevice::Gamepad;
+                }
+                ButtonEvent::Released(button) => {
+                    held.remove(&button);
+                    released.insert(button);
+                }
+                ButtonEvent::Disconnected => held.clear(),
+            }
+        }
+    }
+}
+
+/// Connection to the gamepads, or `None` where the platform offers none.  Not `Send`, so it
+/// lives in the world as a non-send resource.
+#[cfg(feature = "gamepad")]
+pub struct Gamepads(Option<Gilrs>);
+
+/// Stands in for the gamepad connection in builds without the `gamepad` feature, which never
+/// report any button events.
+#[cfg(not(feature = "gamepad"))]
+#[derive(Default)]
+pub struct Gamepads;
+
+#[cfg(feature = "gamepad")]
+impl Default for Gamepads {
+    fn default() -> Self {
+        match Gilrs::new() {
+            Ok(value) => Gamepads(Some(value)),
+            Err(error) => {
+                error!("Gamepads unavailable: {error}");
+                Gamepads(None)
+            }
+        }
+    }
+}
+
+impl Gamepads {
+    /// Takes the button events which arrived since the last call.
+    #[cfg(feature = "gamepad")]
+    pub fn events(&mut self) -> Vec<ButtonEvent> {
+        let Gamepads(Some(gilrs)) = self else {
+            return Vec::new();
+        };
+        std::iter::from_fn(|| gilrs.next_event())
+            .filter_map(|event| match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    GamepadButton::from_gilrs(button).map(ButtonEvent::Pressed)
+                }
+                EventType::ButtonReleased(button, _) => {
+                    GamepadButton::from_gilrs(button).map(ButtonEvent::Released)
+                }
+                EventType::Disconnected => Some(ButtonEvent::Disconnected),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Takes the button events which arrived since the last call.
+    #[cfg(not(feature = "gamepad"))]
+    #[allow(clippy::unused_self)]
+    pub fn events(&mut self) -> Vec<ButtonEvent> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, ButtonEvent, Controls, GamepadButton, InputDevice};
+    use macroquad::input::KeyCode;
+
+    #[test]
+    fn default_controls_bind_every_action() {
+        // arrange
+        let controls = Controls::default();
+
+        // act
+        let unbound: Vec<Action> = Action::ALL
+            .into_iter()
+            .filter(|action| controls.keys(*action).is_empty())
+            .collect();
+
+        // assert
+        assert!(unbound.is_empty(), "{unbound:?} have no keys");
+        assert_eq!(
+            controls.keys(Action::Back),
+            [KeyCode::Escape, KeyCode::Backspace]
+        );
+    }
+
+    #[test]
+    fn player_two_flap_key_answers_no_other_action() {
+        // arrange
+        let controls = Controls::default();
+        let flap_keys = controls.keys(Action::FlapPlayerTwo);
+
+        // act
+        let shared: Vec<Action> = Action::ALL
+            .into_iter()
+            .filter(|action| *action != Action::FlapPlayerTwo)
+            .filter(|action| {
+                controls
+                    .keys(*action)
+                    .iter()
+                    .any(|key| flap_keys.contains(key))
+            })
+            .collect();
+
+        // assert
+        assert!(shared.is_empty(), "{shared:?} share player two's flap key");
+    }
+
+    #[test]
+    fn gamepad_presses_last_until_next_poll_while_buttons_stay_held() {
+        // arrange
+        let mut controls = Controls::default();
+        controls.update_buttons([ButtonEvent::Pressed(GamepadButton::DPadDown)]);
+        let first_poll = controls.any_button(Action::Down, &controls.buttons.pressed);
+
+        // act
+        controls.update_buttons([ButtonEvent::Released(GamepadButton::East)]);
+
+        // assert
+        let states = &controls.buttons;
+        assert!(first_poll);
+        assert!(!controls.any_button(Action::Down, &states.pressed));
+        assert!(controls.any_button(Action::Down, &states.held));
+        assert!(controls.any_button(Action::Back, &states.released));
+        assert!(!controls.any_button(Action::Up, &states.held));
+        assert_eq!(controls.device, InputDevice::Gamepad);
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy_ecs::resource::Resource;
+use macroquad::logging::{error, info};
+
+use crate::{
+    level::Level,
+    progress::{scoring_header, split_scoring_header},
+    resources::Difficulty,
+};
+
+/// Today's attempts and best daily challenge score on each difficulty, kept locally under a
+/// scoring header in the per-user data directory and reset when the date changes.
+pub const DAILY_FILE_NAME: &str = "daily.txt";
+
+/// Calendar date in UTC.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UtcDate {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl UtcDate {
+    #[must_use]
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |value| value.as_secs());
+        #[allow(clippy::cast_possible_wrap)]
+        let days = (seconds / 86_400) as i64;
+        UtcDate::from_days_since_epoch(days)
+    }
+
+    /// Converts days since 1970-01-01 to a Gregorian calendar date.
+    #[must_use]
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        // shift the epoch to 0000-03-01 so leap days fall at the end of each year
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        UtcDate {
+            year,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// Seed shared by everyone playing on this date, readable as the date itself, e.g.
+    /// `20261018`.
+    #[must_use]
+    pub fn seed(self) -> u64 {
+        #[allow(clippy::cast_sign_loss)]
+        let year = self.year.max(0) as u64;
+        year * 10_000 + u64::from(self.month) * 100 + u64::from(self.day)
+    }
+}
+
+impl std::fmt::Display for UtcDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Today's daily challenge course, with local attempts and best scores for the day.
+#[derive(Debug, Resource)]
+pub struct DailyChallenge {
+    pub date: UtcDate,
+    pub level: Level,
+    pub attempts: u32,
+
+    /// Indexed by `Difficulty::index`, so scores are only compared within a difficulty.
+    pub best_scores: [Option<u32>; Difficulty::ALL.len()],
+}
+
+impl DailyChallenge {
+    #[must_use]
+    pub fn new(date: UtcDate) -> Self {
+        DailyChallenge {
+            date,
+            level: Level::generate("level-daily", date.seed()),
+            attempts: 0,
+            best_scores: [None; Difficulty::ALL.len()],
+        }
+    }
+
+    /// Moves on to `today`'s challenge if the date has changed since this one was set, so a
+    /// game left running past midnight starts the new day's course.
+    pub fn refresh(&mut self, today: UtcDate) {
+        if self.date != today {
+            info!("Daily challenge moved on to {today}");
+            *self = DailyChallenge::new(today);
+        }
+    }
+
+    #[must_use]
+    pub fn best_score(&self, difficulty: Difficulty) -> Option<u32> {
+        self.best_scores[difficulty.index()]
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.date.seed()
+    }
+
+    /// Records a finished run, returning `true` if it set today's best score at this
+    /// difficulty.
+    pub fn record(&mut self, difficulty: Difficulty, score: u32) -> bool {
+        let best_score = &mut self.best_scores[difficulty.index()];
+        if best_score.is_none_or(|best| score > best) {
+            *best_score = Some(score);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Restores saved attempts and best scores, which only count when saved on the same date.
+    /// Best scores set under other scoring rules are dropped.
+    pub fn restore(&mut self, text: &str) {
+        let (current_scoring, text) = split_scoring_header(text);
+        let mut fields = text.split_whitespace();
+        if fields.next() != Some(&self.date.to_string()) {
+            return;
+        }
+        self.attempts = fields
+            .next()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default();
+        if !current_scoring {
+            return;
+        }
+        for (best_score, field) in self.best_scores.iter_mut().zip(fields) {
+            *best_score = field.parse().ok();
+        }
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let best_scores = self
+            .best_scores
+            .iter()
+            .map(|value| value.map_or_else(|| String::from("-"), |value| value.to_string()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(
+            "{}{} {} {best_scores}\n",
+            scoring_header(),
+            self.date,
+            self.attempts
+        )
+    }
+
+    /// Today's challenge, picking up attempts already saved today.
+    #[must_use]
+    pub fn load(path: &Path) -> Self {
+        let mut result = DailyChallenge::new(UtcDate::today());
+        match fs::read_to_string(path) {
+            Ok(value) => result.restore(&value),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                info!("No saved daily challenge found");
+            }
+            Err(error) => error!("Unable to read saved daily challenge: {error}"),
+        }
+
+        result
+    }
+
+    pub fn save(&self, path: &Path) {
+        if let Err(error) = fs::write(path, self.to_text()) {
+            error!("Unable to save daily challenge: {error}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DailyChallenge, UtcDate};
+    use crate::{progress::SCORING_VERSION, resources::Difficulty};
+
+    #[test]
+    fn utc_date_from_days_since_epoch_returns_expected_dates() {
+        // arrange
+        let days = [0, 11_016, 20_744];
+
+        // act
+        let result = days.map(UtcDate::from_days_since_epoch);
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                UtcDate {
+                    year: 1970,
+                    month: 1,
+                    day: 1
+                },
+                UtcDate {
+                    year: 2000,
+                    month: 2,
+                    day: 29
+                },
+                UtcDate {
+                    year: 2026,
+                    month: 10,
+                    day: 18
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn utc_date_seed_reads_as_date() {
+        // arrange
+        let date = UtcDate {
+            year: 2026,
+            month: 10,
+            day: 18,
+        };
+
+        // act
+        let result = date.seed();
+
+        // assert
+        assert_eq!(result, 20_261_018);
+        assert_eq!(date.to_string(), "2026-10-18");
+    }
+
+    #[test]
+    fn daily_challenge_restore_ignores_other_dates() {
+        // arrange
+        let date = UtcDate {
+            year: 2026,
+            month: 10,
+            day: 18,
+        };
+        let mut today = DailyChallenge::new(date);
+        let mut yesterday = DailyChallenge::new(date);
+
+        // act
+        today.restore(&format!("scoring {SCORING_VERSION}\n2026-10-18 3 - 12 -\n"));
+        yesterday.restore(&format!("scoring {SCORING_VERSION}\n2026-10-17 3 - 12 -\n"));
+
+        // assert
+        assert_eq!(
+            (today.attempts, today.best_scores),
+            (3, [None, Some(12), None])
+        );
+        assert_eq!((yesterday.attempts, yesterday.best_scores), (0, [None; 3]));
+    }
+    #[test]
+    fn daily_challenge_restore_drops_best_score_set_under_other_scoring_rules() {
+        // arrange
+        let date = UtcDate {
+            year: 2026,
+            month: 10,
+            day: 18,
+        };
+        let mut daily = DailyChallenge::new(date);
+        let mut round_trip = DailyChallenge::new(date);
+        round_trip.attempts = 2;
+        round_trip.record(Difficulty::Hard, 9);
+
+        // act
+        daily.restore("2026-10-18 3 12\n");
+        let text = round_trip.to_text();
+        let mut restored = DailyChallenge::new(date);
+        restored.restore(&text);
+
+        // assert
+        assert_eq!((daily.attempts, daily.best_scores), (3, [None; 3]));
+        assert_eq!(
+            (restored.attempts, restored.best_scores),
+            (2, [None, None, Some(9)])
+        );
+    }
+
+    #[test]
+    fn daily_challenge_keeps_best_scores_per_difficulty() {
+        // arrange
+        let mut daily = DailyChallenge::new(UtcDate::from_days_since_epoch(0));
+        daily.record(Difficulty::Easy, 20);
+
+        // act
+        let result = daily.record(Difficulty::Hard, 5);
+
+        // assert
+        assert!(result);
+        assert_eq!(daily.best_score(Difficulty::Easy), Some(20));
+        assert_eq!(daily.best_score(Difficulty::Normal), None);
+        assert_eq!(daily.best_score(Difficulty::Hard), Some(5));
+    }
+
+    #[test]
+    fn daily_challenge_refresh_moves_on_only_when_the_date_changes() {
+        // arrange
+        let yesterday = UtcDate::from_days_since_epoch(20_743);
+        let today = UtcDate::from_days_since_epoch(20_744);
+        let mut kept = DailyChallenge::new(today);
+        kept.attempts = 2;
+        let mut stale = DailyChallenge::new(yesterday);
+        stale.attempts = 2;
+
+        // act
+        kept.refresh(today);
+        stale.refresh(today);
+
+        // assert
+        assert_eq!((kept.date, kept.attempts), (today, 2));
+        assert_eq!((stale.date, stale.attempts), (today, 0));
+        assert_eq!(stale.level, DailyChallenge::new(today).level);
+    }
+}
@@ -0,0 +1,176 @@
+use macroquad::{
+    camera::{Camera2D, set_camera, set_default_camera},
+    color::{BLACK, WHITE},
+    math::{Rect, Vec2, vec2},
+    texture::{DrawTextureParams, FilterMode, RenderTarget, draw_texture_ex, render_target},
+    window::{clear_background, screen_height, screen_width},
+};
+
+/// Logical width all gameplay and UI code is expressed in, independent of the window size.
+pub const VIRTUAL_WIDTH: f32 = 800.0;
+
+/// Logical height all gameplay and UI code is expressed in, independent of the window size.
+pub const VIRTUAL_HEIGHT: f32 = 600.0;
+
+/// Strip across the top of the screen kept for the HUD while playing.  The water starts below
+/// it: the flipper cannot rise into it, and no gap, pickup or power-up reaches into it.
+pub const HUD_BAND_HEIGHT: f32 = 100.0;
+
+/// Uniform scale and offsets which fit the virtual screen inside the window, leaving bars on
+/// the sides or top and bottom when aspect ratios differ.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Letterbox {
+    pub scale: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+}
+
+impl Default for Letterbox {
+    fn default() -> Self {
+        Letterbox {
+            scale: 1.0,
+            offset_x: 0.0,
+            offset_y: 0.0,
+        }
+    }
+}
+
+impl Letterbox {
+    #[must_use]
+    pub fn new(window_width: f32, window_height: f32) -> Self {
+        let scale = f32::min(window_width / VIRTUAL_WIDTH, window_height / VIRTUAL_HEIGHT);
+
+        Letterbox {
+            scale,
+            offset_x: 0.5 * (window_width - VIRTUAL_WIDTH * scale),
+            offset_y: 0.5 * (window_height - VIRTUAL_HEIGHT * scale),
+        }
+    }
+
+    /// Maps a point in window coordinates, such as the mouse position, onto the virtual screen.
+    #[must_use]
+    pub fn to_virtual(self, window_point: Vec2) -> Vec2 {
+        let Letterbox {
+            scale,
+            offset_x,
+            offset_y,
+        } = self;
+
+        (window_point - vec2(offset_x, offset_y)) / scale
+    }
+}
+
+/// Off-screen render target at the virtual resolution, which gets scaled up to the window each
+/// frame.
+pub struct VirtualScreen {
+    camera: Camera2D,
+    render_target: RenderTarget,
+}
+
+impl Default for VirtualScreen {
+    fn default() -> Self {
+        VirtualScreen::new()
+    }
+}
+
+impl VirtualScreen {
+    #[must_use]
+    pub fn new() -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let render_target = render_target(VIRTUAL_WIDTH as u32, VIRTUAL_HEIGHT as u32);
+        render_target.texture.set_filter(FilterMode::Linear);
+
+        let mut camera =
+            Camera2D::from_display_rect(Rect::new(0.0, 0.0, VIRTUAL_WIDTH, VIRTUAL_HEIGHT));
+        camera.render_target = Some(render_target.clone());
+
+        VirtualScreen {
+            camera,
+            render_target,
+        }
+    }
+
+    /// Redirects subsequent drawing to the virtual screen.
+    pub fn begin(&self) {
+        set_camera(&self.camera);
+    }
+
+    /// Draws the virtual screen to the window, scaled and letterboxed, returning the letterbox
+    /// used.
+    #[must_use]
+    pub fn present(&self) -> Letterbox {
+        let letterbox = Letterbox::new(screen_width(), screen_height());
+        let Letterbox {
+            scale,
+            offset_x,
+            offset_y,
+        } = letterbox;
+
+        set_default_camera();
+        clear_background(BLACK);
+        draw_texture_ex(
+            &self.render_target.texture,
+            offset_x,
+            offset_y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(VIRTUAL_WIDTH * scale, VIRTUAL_HEIGHT * scale)),
+                // render target textures are stored upside down
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+
+        letterbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Letterbox;
+    use float_cmp::approx_eq;
+    use macroquad::math::vec2;
+
+    #[test]
+    fn letterbox_fills_window_with_matching_aspect_ratio() {
+        // arrange
+        let window_width = 1600.0;
+        let window_height = 1200.0;
+
+        // act
+        let result = Letterbox::new(window_width, window_height);
+
+        // assert
+        assert!(approx_eq!(f32, result.scale, 2.0, ulps = 2));
+        assert!(approx_eq!(f32, result.offset_x, 0.0, ulps = 2));
+        assert!(approx_eq!(f32, result.offset_y, 0.0, ulps = 2));
+    }
+
+    #[test]
+    fn letterbox_adds_side_bars_for_wide_window() {
+        // arrange
+        let window_width = 1920.0;
+        let window_height = 1080.0;
+
+        // act
+        let result = Letterbox::new(window_width, window_height);
+
+        // assert
+        assert!(approx_eq!(f32, result.scale, 1.8, ulps = 2));
+        assert!(approx_eq!(f32, result.offset_x, 240.0, ulps = 2));
+        assert!(approx_eq!(f32, result.offset_y, 0.0, ulps = 2));
+    }
+
+    #[test]
+    fn to_virtual_removes_side_bar_and_scale() {
+        // arrange
+        let letterbox = Letterbox::new(1920.0, 1080.0);
+
+        // act
+        let result = letterbox.to_virtual(vec2(960.0, 540.0));
+
+        // assert
+        assert!(approx_eq!(f32, result.x, 400.0, ulps = 2));
+        assert!(approx_eq!(f32, result.y, 300.0, ulps = 2));
+    }
+}
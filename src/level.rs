@@ -0,0 +1,866 @@
+use std::fs;
+
+use bevy_ecs::resource::Resource;
+use macroquad::logging::{error, warn};
+
+use crate::{
+    components::{CollectibleKind, Keyframe, MotionPath, MotionPaths, PowerUpKind},
+    display::{HUD_BAND_HEIGHT, VIRTUAL_HEIGHT, VIRTUAL_WIDTH},
+    random::SeededRandom,
+    resources::BASE_PAN_SPEED,
+};
+
+/// Obstacle placement; gap values are the resting values any motion is relative to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObstacleSpec {
+    pub x: f32,
+    pub gap_length: f32,
+    pub gap_y_displacement: f32,
+    pub motion: Option<MotionPaths>,
+}
+
+impl ObstacleSpec {
+    #[must_use]
+    pub fn fixed(x: f32, gap_length: f32, gap_y_displacement: f32) -> Self {
+        ObstacleSpec {
+            x,
+            gap_length,
+            gap_y_displacement,
+            motion: None,
+        }
+    }
+
+    /// Gap length and top displacement with the gap length scaled about the gap centre.
+    #[must_use]
+    pub fn scaled_gap(&self, scale: f32) -> (f32, f32) {
+        let gap_length = self.gap_length * scale;
+        let gap_y_displacement = self.gap_y_displacement - 0.5 * (gap_length - self.gap_length);
+
+        (gap_length, gap_y_displacement)
+    }
+}
+
+/// Collectible placement, with `x` and `y` giving the top-left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectibleSpec {
+    pub kind: CollectibleKind,
+    pub x: f32,
+    pub y: f32,
+    pub value: u32,
+}
+
+impl CollectibleSpec {
+    #[must_use]
+    pub fn new(kind: CollectibleKind, x: f32, y: f32) -> Self {
+        CollectibleSpec {
+            kind,
+            x,
+            y,
+            value: kind.default_value(),
+        }
+    }
+}
+
+/// Power-up placement, with `x` and `y` giving the top-left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PowerUpSpec {
+    pub kind: PowerUpKind,
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl Medal {
+    /// Id of the medal's name in the string table.
+    #[must_use]
+    pub fn message_id(self) -> &'static str {
+        match self {
+            Medal::Bronze => "medal-bronze",
+            Medal::Silver => "medal-silver",
+            Medal::Gold => "medal-gold",
+        }
+    }
+}
+
+/// Time trial finishing times, in seconds, needed for each medal.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MedalTimes {
+    pub gold: f32,
+    pub silver: f32,
+    pub bronze: f32,
+}
+
+impl MedalTimes {
+    /// Thresholds for a run at `speed_scale` times the base speed, which takes proportionally
+    /// longer to cover the course when slower.
+    #[must_use]
+    pub fn for_speed(&self, speed_scale: f32) -> Self {
+        MedalTimes {
+            gold: self.gold / speed_scale,
+            silver: self.silver / speed_scale,
+            bronze: self.bronze / speed_scale,
+        }
+    }
+
+    /// Best medal earned by a finishing time, with times on a threshold earning that medal.
+    #[must_use]
+    pub fn medal(&self, time: f32) -> Option<Medal> {
+        if time <= self.gold {
+            Some(Medal::Gold)
+        } else if time <= self.silver {
+            Some(Medal::Silver)
+        } else if time <= self.bronze {
+            Some(Medal::Bronze)
+        } else {
+            None
+        }
+    }
+
+    /// Next medal still within reach at `time`, with its threshold.
+    #[must_use]
+    pub fn next_target(&self, time: f32) -> Option<(Medal, f32)> {
+        [
+            (Medal::Gold, self.gold),
+            (Medal::Silver, self.silver),
+            (Medal::Bronze, self.bronze),
+        ]
+        .into_iter()
+        .find(|(_, threshold)| time <= *threshold)
+    }
+}
+
+/// Course currently loaded into the world, empty until a run starts.
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+pub struct Level {
+    /// Id of the level's name in the string table.
+    pub message_id: String,
+    pub finish_line_x: f32,
+    /// Horizontal positions of checkpoints, used as respawn points when lives are enabled.
+    pub checkpoints: Vec<f32>,
+    pub medal_times: MedalTimes,
+    pub obstacles: Vec<ObstacleSpec>,
+    pub collectibles: Vec<CollectibleSpec>,
+    pub power_ups: Vec<PowerUpSpec>,
+}
+
+/// Parses whitespace-separated numbers, or none if any word is not a number.
+fn parse_numbers(value: &str) -> Option<Vec<f32>> {
+    value
+        .split_whitespace()
+        .map(|word| word.parse().ok())
+        .collect()
+}
+
+/// Parses `sine amplitude period`, `ping-pong amplitude period` or `keyframes time:offset ...`.
+fn parse_motion_path(value: &str) -> Option<MotionPath> {
+    let (kind, arguments) = value.split_once(char::is_whitespace)?;
+    match kind {
+        "sine" | "ping-pong" => {
+            let [amplitude, period] = parse_numbers(arguments)?.try_into().ok()?;
+            Some(if kind == "sine" {
+                MotionPath::Sine { amplitude, period }
+            } else {
+                MotionPath::PingPong { amplitude, period }
+            })
+        }
+        "keyframes" => arguments
+            .split_whitespace()
+            .map(|keyframe| {
+                let (time, offset) = keyframe.split_once(':')?;
+                Some(Keyframe {
+                    time: time.parse().ok()?,
+                    offset: offset.parse().ok()?,
+                })
+            })
+            .collect::<Option<_>>()
+            .map(MotionPath::Keyframes),
+        _ => None,
+    }
+}
+
+/// Parses `x gap-length gap-top`, then any `; property path` motion clauses.
+fn parse_obstacle(value: &str) -> Option<ObstacleSpec> {
+    let mut clauses = value.split(';');
+    let [x, gap_length, gap_y_displacement] = parse_numbers(clauses.next()?)?.try_into().ok()?;
+    let mut result = ObstacleSpec::fixed(x, gap_length, gap_y_displacement);
+    for clause in clauses {
+        let (property, path) = clause.trim().split_once(char::is_whitespace)?;
+        let paths = result.motion.get_or_insert_with(MotionPaths::default);
+        let slot = match property {
+            "x" => &mut paths.x,
+            "gap-y" => &mut paths.gap_y,
+            "gap-length" => &mut paths.gap_length,
+            _ => return None,
+        };
+        *slot = Some(parse_motion_path(path.trim())?);
+    }
+
+    Some(result)
+}
+
+/// Parses `x y` into a collectible placed by its top-left corner.
+fn parse_collectible(kind: CollectibleKind, value: &str) -> Option<CollectibleSpec> {
+    let [x, y] = parse_numbers(value)?.try_into().ok()?;
+    Some(CollectibleSpec::new(kind, x, y))
+}
+
+/// Parses `x y` into a power-up placed by its top-left corner.
+fn parse_power_up(kind: PowerUpKind, value: &str) -> Option<PowerUpSpec> {
+    let [x, y] = parse_numbers(value)?.try_into().ok()?;
+    Some(PowerUpSpec { kind, x, y })
+}
+
+/// Space kept clear above and below generated gaps, at the extremes of any motion, measured
+/// from the HUD band at the top.
+const GENERATED_GAP_MARGIN: f32 = 60.0;
+
+impl Level {
+    /// Checks every obstacle's motion paths, leaving at rest any property whose path cannot be
+    /// followed.
+    #[must_use]
+    pub fn validated(mut self) -> Self {
+        for spec in &mut self.obstacles {
+            let Some(paths) = &mut spec.motion else {
+                continue;
+            };
+            let MotionPaths {
+                x,
+                gap_y,
+                gap_length,
+            } = paths;
+            for (property, path) in [("x", x), ("gap_y", gap_y), ("gap_length", gap_length)] {
+                if let Some(problem) = path.as_ref().and_then(MotionPath::problem) {
+                    error!(
+                        "Leaving {property} at rest for the obstacle at x = {} in {}: {problem}",
+                        spec.x, self.message_id
+                    );
+                    *path = None;
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Builds a course procedurally from `seed`; the same seed always gives the same course.
+    #[must_use]
+    pub fn generate(message_id: &'static str, seed: u64) -> Self {
+        let mut random = SeededRandom::new(seed);
+        let finish_line_x = 5.0 * VIRTUAL_WIDTH;
+        let mut obstacles = Vec::new();
+        let mut checkpoints = Vec::new();
+        let mut collectibles = Vec::new();
+        let mut power_ups = Vec::new();
+
+        let mut x = 600.0;
+        while x < finish_line_x - 500.0 {
+            let spacing = random.range(280.0, 380.0);
+            let gap_length = random.range(70.0, 100.0);
+            let motion_choice = random.next_f32();
+            let (motion, gap_y_amplitude) = if motion_choice < 0.3 {
+                let amplitude = random.range(30.0, 80.0);
+                let paths = MotionPaths {
+                    gap_y: Some(MotionPath::Sine {
+                        amplitude,
+                        period: random.range(2.0, 4.0),
+                    }),
+                    ..Default::default()
+                };
+                (Some(paths), amplitude)
+            } else if motion_choice < 0.45 {
+                let paths = MotionPaths {
+                    gap_length: Some(MotionPath::PingPong {
+                        amplitude: random.range(10.0, 20.0),
+                        period: random.range(2.0, 3.5),
+                    }),
+                    ..Default::default()
+                };
+                (Some(paths), 0.0)
+            } else {
+                (None, 0.0)
+            };
+            let gap_y_displacement = random.range(
+                HUD_BAND_HEIGHT + GENERATED_GAP_MARGIN + gap_y_amplitude,
+                VIRTUAL_HEIGHT - GENERATED_GAP_MARGIN - gap_length - gap_y_amplitude,
+            );
+            let gap_centre = gap_y_displacement + 0.5 * gap_length;
+
+            if !obstacles.is_empty() && random.chance(0.25) {
+                const KINDS: [PowerUpKind; 3] = [
+                    PowerUpKind::Magnet,
+                    PowerUpKind::Shield,
+                    PowerUpKind::SlowMotion,
+                ];
+                #[allow(clippy::cast_possible_truncation)]
+                let kind = KINDS[(random.next_u64() % KINDS.len() as u64) as usize];
+                power_ups.push(PowerUpSpec {
+                    kind,
+                    x: x - 120.0,
+                    y: gap_centre - 8.0,
+                });
+            }
+            obstacles.push(ObstacleSpec {
+                motion,
+                ..ObstacleSpec::fixed(x, gap_length, gap_y_displacement)
+            });
+            if random.chance(0.5) {
+                let kind = if random.chance(0.3) {
+                    CollectibleKind::Pearl
+                } else {
+                    CollectibleKind::Shell
+                };
+                let y = (gap_centre + random.range(-40.0, 40.0)).clamp(
+                    HUD_BAND_HEIGHT + GENERATED_GAP_MARGIN,
+                    VIRTUAL_HEIGHT - GENERATED_GAP_MARGIN,
+                );
+                collectibles.push(CollectibleSpec::new(kind, x + 0.5 * spacing, y));
+            }
+            if obstacles.len() % 4 == 0 {
+                checkpoints.push(x + 0.5 * spacing);
+            }
+            x += spacing;
+        }
+
+        // medals relative to the time taken at normal speed
+        let par_time = (finish_line_x - 20.0) / BASE_PAN_SPEED;
+
+        Level {
+            message_id: message_id.to_string(),
+            finish_line_x,
+            checkpoints,
+            medal_times: MedalTimes {
+                gold: 0.85 * par_time,
+                silver: 0.95 * par_time,
+                bronze: 1.05 * par_time,
+            },
+            obstacles,
+            collectibles,
+            power_ups,
+        }
+        .validated()
+    }
+
+    /// Parses `setting = value` lines, skipping blank lines, `#` comments and lines that cannot
+    /// be read.  `obstacle` and pickup lines each add one to the course.
+    ///
+    /// # Errors
+    ///
+    /// Returns what is missing when the source has no name, finish line or medal times.
+    pub fn parse(source: &str) -> Result<Self, &'static str> {
+        let mut message_id = None;
+        let mut finish_line_x = None;
+        let mut medal_times = None;
+        let mut checkpoints = Vec::new();
+        let mut obstacles = Vec::new();
+        let mut collectibles = Vec::new();
+        let mut power_ups = Vec::new();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let read = line.split_once('=').and_then(|(key, value)| {
+                let value = value.trim();
+                match key.trim() {
+                    "name" => message_id = Some(value.to_string()),
+                    "finish-line" => finish_line_x = Some(value.parse().ok()?),
+                    "checkpoints" => checkpoints = parse_numbers(value)?,
+                    "medals" => {
+                        let [gold, silver, bronze] = parse_numbers(value)?.try_into().ok()?;
+                        medal_times = Some(MedalTimes {
+                            gold,
+                            silver,
+                            bronze,
+                        });
+                    }
+                    "obstacle" => obstacles.push(parse_obstacle(value)?),
+                    "pearl" => collectibles.push(parse_collectible(CollectibleKind::Pearl, value)?),
+                    "shell" => collectibles.push(parse_collectible(CollectibleKind::Shell, value)?),
+                    "magnet" => power_ups.push(parse_power_up(PowerUpKind::Magnet, value)?),
+                    "shield" => power_ups.push(parse_power_up(PowerUpKind::Shield, value)?),
+                    "slow-motion" => {
+                        power_ups.push(parse_power_up(PowerUpKind::SlowMotion, value)?);
+                    }
+                    _ => return None,
+                }
+                Some(())
+            });
+            if read.is_none() {
+                error!(
+                    "Ignoring level line {} that cannot be read: `{line}`",
+                    index + 1
+                );
+            }
+        }
+
+        Ok(Level {
+            message_id: message_id.ok_or("it has no name")?,
+            finish_line_x: finish_line_x.ok_or("it has no finish line")?,
+            checkpoints,
+            medal_times: medal_times.ok_or("it has no medal times")?,
+            obstacles,
+            collectibles,
+            power_ups,
+        })
+    }
+}
+
+/// Levels of the campaign, in the order they unlock.  Each has a file in `assets/levels`, read
+/// when the game starts, with a copy built into the executable in case the file is missing or
+/// unplayable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CampaignLevel {
+    Shallows,
+    KelpForest,
+    Trench,
+}
+
+impl CampaignLevel {
+    pub const ALL: [CampaignLevel; 3] = [
+        CampaignLevel::Shallows,
+        CampaignLevel::KelpForest,
+        CampaignLevel::Trench,
+    ];
+
+    /// Name of the level's file.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            CampaignLevel::Shallows => "shallows",
+            CampaignLevel::KelpForest => "kelp-forest",
+            CampaignLevel::Trench => "trench",
+        }
+    }
+
+    fn built_in_source(self) -> &'static str {
+        match self {
+            CampaignLevel::Shallows => include_str!("../assets/levels/shallows.txt"),
+            CampaignLevel::KelpForest => include_str!("../assets/levels/kelp-forest.txt"),
+            CampaignLevel::Trench => include_str!("../assets/levels/trench.txt"),
+        }
+    }
+
+    fn built_in(self) -> Level {
+        Level::parse(self.built_in_source()).unwrap_or_else(|problem| {
+            error!(
+                "Unable to use the built-in {} level: {problem}",
+                self.code()
+            );
+            Level::default()
+        })
+    }
+
+    /// Level from `assets/levels`, falling back to the built-in copy if the file cannot be read
+    /// or lacks a setting the course needs.
+    #[must_use]
+    pub fn load(self) -> Level {
+        let path = format!("./assets/levels/{}.txt", self.code());
+        let source = match fs::read_to_string(&path) {
+            Ok(value) => value,
+            Err(error) => {
+                warn!("Unable to read {path}, using the built-in level: {error}");
+                return self.built_in();
+            }
+        };
+        Level::parse(&source).unwrap_or_else(|problem| {
+            error!("Unable to use {path}, using the built-in level: {problem}");
+            self.built_in()
+        })
+    }
+}
+
+/// Ordered list of levels; each one unlocks once the level before it is completed.
+#[derive(Debug, Resource)]
+pub struct Campaign {
+    pub levels: Vec<Level>,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Campaign {
+            levels: CampaignLevel::ALL
+                .into_iter()
+                .map(|level| level.load().validated())
+                .collect(),
+        }
+    }
+}
+
+/// Campaign level picked on the level select screen, and whether the current run has been
+/// spawned.  Clearing `spawned` restarts the run on the next playing frame.
+#[derive(Debug, Default, Resource)]
+pub struct CurrentLevel {
+    pub index: usize,
+    pub spawned: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Campaign, CampaignLevel, CollectibleSpec, Level, Medal, MedalTimes, ObstacleSpec,
+        PowerUpSpec,
+    };
+    use crate::{
+        components::{CollectibleKind, Keyframe, MotionPath, MotionPaths, PowerUpKind},
+        display::{HUD_BAND_HEIGHT, VIRTUAL_HEIGHT},
+        locale::{Locale, Strings},
+        resources::Difficulty,
+    };
+    use float_cmp::approx_eq;
+
+    /// Lowest and highest offsets `path` reaches, or none without a path.
+    fn motion_range(path: Option<&MotionPath>) -> (f32, f32) {
+        path.map_or((0.0, 0.0), MotionPath::range)
+    }
+
+    /// Highest gap top and lowest gap bottom over any motion, with the gap length scaled by
+    /// `scale`.
+    fn gap_extent(spec: &ObstacleSpec, scale: f32) -> (f32, f32) {
+        let (gap_length, gap_y_displacement) = spec.scaled_gap(scale);
+        let motion = spec.motion.as_ref();
+        let (gap_y_low, gap_y_high) = motion_range(motion.and_then(|value| value.gap_y.as_ref()));
+        let (_, gap_length_high) = motion_range(motion.and_then(|value| value.gap_length.as_ref()));
+
+        (
+            gap_y_displacement + gap_y_low - 0.5 * gap_length_high,
+            gap_y_displacement + gap_length + gap_y_high + 0.5 * gap_length_high,
+        )
+    }
+
+    #[test]
+    fn generate_repeats_course_for_seed() {
+        // arrange
+        let seed = 20_261_018;
+
+        // act
+        let result = Level::generate("level-daily", seed);
+
+        // assert
+        assert_eq!(result, Level::generate("level-daily", seed));
+        assert_ne!(
+            result.obstacles,
+            Level::generate("level-daily", seed + 1).obstacles
+        );
+    }
+
+    #[test]
+    fn generate_keeps_gaps_on_screen() {
+        for seed in 0..50 {
+            // arrange
+            let level = Level::generate("level-daily", seed);
+
+            // act
+            let result = level.obstacles.iter().all(|spec| {
+                let (top, bottom) = gap_extent(spec, 1.0);
+                top >= HUD_BAND_HEIGHT && bottom <= VIRTUAL_HEIGHT
+            });
+
+            // assert
+            assert!(result, "seed {seed} places a gap off screen");
+        }
+    }
+
+    #[test]
+    fn campaign_keeps_course_below_hud_band_at_every_difficulty() {
+        // arrange
+        let campaign = Campaign::default();
+
+        // act
+        let out_of_water: Vec<(&str, f32)> = campaign
+            .levels
+            .iter()
+            .flat_map(|level| {
+                let gaps = level.obstacles.iter().flat_map(|spec| {
+                    Difficulty::ALL.iter().filter_map(move |difficulty| {
+                        let (top, bottom) = gap_extent(spec, difficulty.gap_scale());
+                        (top < HUD_BAND_HEIGHT || bottom > VIRTUAL_HEIGHT).then_some(spec.x)
+                    })
+                });
+                let pickups = level
+                    .collectibles
+                    .iter()
+                    .map(|spec| (spec.x, spec.y))
+                    .chain(level.power_ups.iter().map(|spec| (spec.x, spec.y)))
+                    .filter(|(_, y)| *y < HUD_BAND_HEIGHT)
+                    .map(|(x, _)| x);
+                gaps.chain(pickups).map(|x| (level.message_id.as_str(), x))
+            })
+            .collect();
+
+        // assert
+        assert!(
+            out_of_water.is_empty(),
+            "{out_of_water:?} reach the HUD band"
+        );
+    }
+
+    #[test]
+    fn validated_leaves_properties_with_broken_paths_at_rest() {
+        // arrange
+        let sine = |period| MotionPath::Sine {
+            amplitude: 40.0,
+            period,
+        };
+        let level = Level {
+            obstacles: vec![ObstacleSpec {
+                motion: Some(MotionPaths {
+                    x: Some(sine(0.0)),
+                    gap_y: Some(sine(2.0)),
+                    gap_length: None,
+                }),
+                ..ObstacleSpec::fixed(600.0, 100.0, 300.0)
+            }],
+            ..Level::default()
+        };
+
+        // act
+        let result = level.validated();
+
+        // assert
+        assert_eq!(
+            result.obstacles[0].motion,
+            Some(MotionPaths {
+                x: None,
+                gap_y: Some(sine(2.0)),
+                gap_length: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_reads_settings_motion_paths_and_pickups() {
+        // arrange
+        let source = "\
+            # comment\n\
+            name = level-test\n\
+            finish-line = 2000\n\
+            checkpoints = 800 1400\n\
+            medals = 10 11.5 13\n\
+            \n\
+            obstacle = 600 90 250\n\
+            obstacle = 900 80 300; gap-y sine 80 3; x ping-pong 40 2\n\
+            obstacle = 1200 70 400; gap-length keyframes 0:0 1:-20 2:0\n\
+            pearl = 602.5 345\n\
+            slow-motion = 1050 330\n";
+
+        // act
+        let result = Level::parse(source);
+
+        // assert
+        assert_eq!(
+            result,
+            Ok(Level {
+                message_id: "level-test".to_string(),
+                finish_line_x: 2000.0,
+                checkpoints: vec![800.0, 1400.0],
+                medal_times: MedalTimes {
+                    gold: 10.0,
+                    silver: 11.5,
+                    bronze: 13.0,
+                },
+                obstacles: vec![
+                    ObstacleSpec::fixed(600.0, 90.0, 250.0),
+                    ObstacleSpec {
+                        motion: Some(MotionPaths {
+                            x: Some(MotionPath::PingPong {
+                                amplitude: 40.0,
+                                period: 2.0,
+                            }),
+                            gap_y: Some(MotionPath::Sine {
+                                amplitude: 80.0,
+                                period: 3.0,
+                            }),
+                            gap_length: None,
+                        }),
+                        ..ObstacleSpec::fixed(900.0, 80.0, 300.0)
+                    },
+                    ObstacleSpec {
+                        motion: Some(MotionPaths {
+                            gap_length: Some(MotionPath::Keyframes(
+                                [(0.0, 0.0), (1.0, -20.0), (2.0, 0.0)]
+                                    .map(|(time, offset)| Keyframe { time, offset })
+                                    .to_vec(),
+                            )),
+                            ..Default::default()
+                        }),
+                        ..ObstacleSpec::fixed(1200.0, 70.0, 400.0)
+                    },
+                ],
+                collectibles: vec![CollectibleSpec::new(CollectibleKind::Pearl, 602.5, 345.0)],
+                power_ups: vec![PowerUpSpec {
+                    kind: PowerUpKind::SlowMotion,
+                    x: 1050.0,
+                    y: 330.0,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_skips_unreadable_lines_but_needs_name_finish_line_and_medals() {
+        // arrange
+        let settings = "name = level-test\nfinish-line = 2000\nmedals = 10 11.5 13\n";
+        let unreadable = "obstacle = 600 90\nobstacle = 900 80 300; gap-y wobble 1 2\nreef = 1 2\n";
+
+        // act
+        let result = Level::parse(&format!("{settings}{unreadable}obstacle = 600 90 250\n"));
+        let missing = Level::parse("name = level-test\nmedals = 10 11.5 13\n");
+
+        // assert
+        assert_eq!(
+            result.map(|level| level.obstacles),
+            Ok(vec![ObstacleSpec::fixed(600.0, 90.0, 250.0)])
+        );
+        assert_eq!(missing, Err("it has no finish line"));
+    }
+
+    #[test]
+    fn every_built_in_level_parses() {
+        // arrange
+        let levels = CampaignLevel::ALL;
+
+        // act
+        let unplayable: Vec<(&str, &str)> = levels
+            .into_iter()
+            .filter_map(|level| {
+                let problem = Level::parse(level.built_in_source()).err()?;
+                Some((level.code(), problem))
+            })
+            .collect();
+
+        // assert
+        assert!(unplayable.is_empty(), "{unplayable:?}");
+    }
+
+    #[test]
+    fn shallows_keeps_fixed_obstacles() {
+        // arrange
+        let level = CampaignLevel::Shallows.built_in();
+
+        // act
+        let result: Vec<(f32, f32, f32, bool)> = level
+            .obstacles
+            .iter()
+            .map(|spec| {
+                let moves = spec.motion.is_some();
+                (spec.x, spec.gap_length, spec.gap_y_displacement, moves)
+            })
+            .collect();
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                (600.0, 100.0, 300.0, false),
+                (900.0, 75.0, 350.0, false),
+                (1200.0, 75.0, 300.0, false),
+                (1500.0, 75.0, 300.0, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn every_level_name_is_in_the_string_table() {
+        // arrange
+        let strings = Strings::new(Locale::English);
+        let campaign = Campaign::default();
+        let daily = Level::generate("level-daily", 1);
+
+        // act
+        let missing: Vec<&str> = campaign
+            .levels
+            .iter()
+            .chain(std::iter::once(&daily))
+            .map(|level| level.message_id.as_str())
+            .filter(|id| strings.get(id) == *id)
+            .collect();
+
+        // assert
+        assert!(missing.is_empty(), "{missing:?} have no name");
+    }
+
+    #[test]
+    fn medal_times_for_speed_keep_gold_in_reach_at_every_difficulty() {
+        // arrange
+        let medal_times = MedalTimes {
+            gold: 17.0,
+            silver: 20.0,
+            bronze: 24.0,
+        };
+
+        // act
+        let result = Difficulty::ALL.map(|difficulty| {
+            // a gold-paced run on Normal, played at this difficulty's speed
+            let time = medal_times.gold / difficulty.speed_scale();
+            medal_times.for_speed(difficulty.speed_scale()).medal(time)
+        });
+
+        // assert
+        assert_eq!(result, [Some(Medal::Gold); 3]);
+        assert_eq!(medal_times.for_speed(1.0), medal_times);
+    }
+
+    #[test]
+    fn medal_awards_best_threshold_met() {
+        // arrange
+        let medal_times = MedalTimes {
+            gold: 10.0,
+            silver: 12.0,
+            bronze: 14.0,
+        };
+
+        // act
+        let result = [
+            medal_times.medal(9.5),
+            medal_times.medal(12.0),
+            medal_times.medal(13.0),
+            medal_times.medal(14.5),
+        ];
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                Some(Medal::Gold),
+                Some(Medal::Silver),
+                Some(Medal::Bronze),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn next_target_skips_medals_already_out_of_reach() {
+        // arrange
+        let medal_times = MedalTimes {
+            gold: 10.0,
+            silver: 12.0,
+            bronze: 14.0,
+        };
+
+        // act
+        let result = medal_times.next_target(11.0);
+
+        // assert
+        assert_eq!(result, Some((Medal::Silver, 12.0)));
+    }
+
+    #[test]
+    fn scaled_gap_keeps_gap_centre() {
+        // arrange
+        let spec = ObstacleSpec::fixed(600.0, 100.0, 300.0);
+
+        // act
+        let (gap_length, gap_y_displacement) = spec.scaled_gap(1.5);
+
+        // assert
+        assert!(approx_eq!(f32, gap_length, 150.0, ulps = 2));
+        assert!(approx_eq!(f32, gap_y_displacement, 275.0, ulps = 2));
+    }
+}
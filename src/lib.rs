@@ -0,0 +1,22 @@
+#![warn(clippy::all, clippy::pedantic)]
+
+pub mod asset_manager;
+pub mod collision;
+pub mod components;
+pub mod controls;
+pub mod daily;
+pub mod display;
+pub mod level;
+pub mod locale;
+pub mod menu;
+pub mod preferences;
+pub mod progress;
+pub mod random;
+pub mod resources;
+pub mod sound;
+pub mod state;
+pub mod storage;
+pub mod systems;
+pub mod theme;
+pub mod ui;
+pub mod widget;
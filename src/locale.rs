@@ -0,0 +1,294 @@
+use std::{borrow::Cow, collections::HashMap, fmt::Display, fs, ops::RangeInclusive};
+
+use bevy_ecs::resource::Resource;
+use macroquad::logging::{error, warn};
+
+/// Languages the game ships text for.  Each has a string table in `assets/locales`, read when
+/// the locale is picked, with a copy built into the executable in case the file is missing.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Locale {
+    #[default]
+    English,
+    French,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::French];
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        let index = Locale::ALL
+            .iter()
+            .position(|value| *value == self)
+            .unwrap_or_default();
+        Locale::ALL[(index + 1) % Locale::ALL.len()]
+    }
+
+    /// Language code naming the locale's string table file, also used in saved preferences.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::French => "fr",
+        }
+    }
+
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        Locale::ALL.into_iter().find(|value| value.code() == code)
+    }
+
+    fn built_in_source(self) -> &'static str {
+        match self {
+            Locale::English => include_str!("../assets/locales/en.txt"),
+            Locale::French => include_str!("../assets/locales/fr.txt"),
+        }
+    }
+
+    /// String table from `assets/locales`, falling back to the built-in copy if the file cannot
+    /// be read.
+    fn source(self) -> Cow<'static, str> {
+        let path = format!("./assets/locales/{}.txt", self.code());
+        match fs::read_to_string(&path) {
+            Ok(value) => Cow::Owned(value),
+            Err(error) => {
+                warn!("Unable to read {path}, using the built-in string table: {error}");
+                Cow::Borrowed(self.built_in_source())
+            }
+        }
+    }
+}
+
+/// Messages for one locale, keyed by message id.
+#[derive(Debug, Default, PartialEq)]
+struct StringTable {
+    /// Locale name, in its own language.
+    name: String,
+
+    /// Font to draw the locale with when its script is outside the Latin fonts shipped.
+    font: Option<String>,
+    messages: HashMap<String, String>,
+}
+
+impl StringTable {
+    /// Parses `message-id = text` lines, skipping blank lines and `#` comments.  Lines starting
+    /// `@` set locale properties.
+    fn parse(source: &str) -> Self {
+        let mut result = StringTable::default();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                error!(
+                    "Ignoring string table line {} without `=`: `{line}`",
+                    index + 1
+                );
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim().to_string());
+            match key {
+                "@name" => result.name = value,
+                "@font" => result.font = Some(value),
+                _ => {
+                    result.messages.insert(key.to_string(), value);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Whether every message can be drawn with the fonts the game ships.
+    fn shipped_fonts_cover(&self) -> bool {
+        self.messages
+            .values()
+            .flat_map(|value| value.chars())
+            .all(|character| {
+                SHIPPED_FONT_COVERAGE
+                    .iter()
+                    .any(|range| range.contains(&character))
+            })
+    }
+}
+
+/// Characters every shipped font has a glyph for.  The Overpass and Pacifico files are the
+/// `latin` web subset: Latin-1 with a few typographic extras, so no Latin Extended-A letters
+/// such as `ł` or `ř`.
+const SHIPPED_FONT_COVERAGE: [RangeInclusive<char>; 20] = [
+    ' '..='~',
+    '\u{a0}'..='\u{ff}',
+    '\u{131}'..='\u{131}',
+    '\u{152}'..='\u{153}',
+    '\u{2bc}'..='\u{2bc}',
+    '\u{2c6}'..='\u{2c6}',
+    '\u{2da}'..='\u{2da}',
+    '\u{2dc}'..='\u{2dc}',
+    '\u{2013}'..='\u{2014}',
+    '\u{2018}'..='\u{201a}',
+    '\u{201c}'..='\u{201e}',
+    '\u{2022}'..='\u{2022}',
+    '\u{2026}'..='\u{2026}',
+    '\u{2039}'..='\u{203a}',
+    '\u{2044}'..='\u{2044}',
+    '\u{2074}'..='\u{2074}',
+    '\u{20ac}'..='\u{20ac}',
+    '\u{2122}'..='\u{2122}',
+    '\u{2212}'..='\u{2212}',
+    '\u{2215}'..='\u{2215}',
+];
+
+/// On-screen text for the chosen locale.  Messages missing from the locale fall back to
+/// English, then to the message id itself, so gaps show up on screen rather than as blanks.
+#[derive(Debug, Resource)]
+pub struct Strings {
+    locale: Locale,
+    table: StringTable,
+    english: StringTable,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Strings::new(Locale::default())
+    }
+}
+
+impl Strings {
+    #[must_use]
+    pub fn new(locale: Locale) -> Self {
+        let table = StringTable::parse(&locale.source());
+        if table.font.is_none() && !table.shipped_fonts_cover() {
+            warn!(
+                "The {} string table uses characters the shipped fonts lack, but names no `@font`.",
+                table.name
+            );
+        }
+
+        Strings {
+            locale,
+            table,
+            english: StringTable::parse(&Locale::English.source()),
+        }
+    }
+
+    #[must_use]
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Locale name, in its own language.
+    #[must_use]
+    pub fn locale_name(&self) -> &str {
+        &self.table.name
+    }
+
+    /// Path of the font this locale needs in place of the shipped Latin fonts, if any.
+    #[must_use]
+    pub fn script_font(&self) -> Option<&str> {
+        self.table.font.as_deref()
+    }
+
+    pub fn get<'a>(&'a self, id: &'a str) -> &'a str {
+        self.table
+            .messages
+            .get(id)
+            .or_else(|| self.english.messages.get(id))
+            .map_or(id, String::as_str)
+    }
+
+    /// Message with each `{name}` placeholder replaced by the matching argument.
+    pub fn format(&self, id: &str, arguments: &[(&str, &dyn Display)]) -> String {
+        let mut result = self.get(id).to_string();
+        for (name, value) in arguments {
+            result = result.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Locale, StringTable, Strings};
+
+    #[test]
+    fn parse_reads_messages_and_locale_properties() {
+        // arrange
+        let source =
+            "# comment\n@name = Test\n@font = ./assets/test.ttf\n\ngreeting = Hello, {name}!\n";
+
+        // act
+        let result = StringTable::parse(source);
+
+        // assert
+        assert_eq!(result.name, "Test");
+        assert_eq!(result.font.as_deref(), Some("./assets/test.ttf"));
+        assert_eq!(result.messages["greeting"], "Hello, {name}!");
+    }
+
+    #[test]
+    fn shipped_fonts_cover_latin_1_but_not_latin_extended_letters() {
+        // arrange
+        let sources = [
+            "greeting = Déjà vu – « Ça va ? » €5…",
+            "greeting = Cześć, łódź",
+            "greeting = Dobrý den, řeka",
+        ];
+
+        // act
+        let result = sources.map(|source| StringTable::parse(source).shipped_fonts_cover());
+
+        // assert
+        assert_eq!(result, [true, false, false]);
+    }
+
+    #[test]
+    fn format_fills_placeholders() {
+        // arrange
+        let strings = Strings::new(Locale::English);
+
+        // act
+        let result = strings.format("race-winner", &[("number", &2)]);
+
+        // assert
+        assert_eq!(result, "Player 2 wins!");
+    }
+
+    #[test]
+    fn get_falls_back_to_english_then_id() {
+        // arrange
+        let mut strings = Strings::new(Locale::French);
+        strings.table.messages.remove("menu-play");
+
+        // act
+        let result = [strings.get("menu-play"), strings.get("no-such-message")];
+
+        // assert
+        assert_eq!(result, ["Play", "no-such-message"]);
+    }
+
+    #[test]
+    fn every_locale_has_every_english_message() {
+        // arrange
+        let english = StringTable::parse(&Locale::English.source());
+
+        // act
+        let tables: Vec<StringTable> = Locale::ALL
+            .iter()
+            .map(|locale| StringTable::parse(&locale.source()))
+            .collect();
+
+        // assert
+        for table in tables {
+            let mut missing: Vec<&String> = english
+                .messages
+                .keys()
+                .filter(|key| !table.messages.contains_key(*key))
+                .collect();
+            missing.sort();
+            assert!(missing.is_empty(), "{} is missing {missing:?}", table.name);
+            assert!(table.font.is_some() || table.shipped_fonts_cover());
+        }
+    }
+}
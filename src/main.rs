@@ -1,37 +1,45 @@
 #![warn(clippy::all, clippy::pedantic)]
 
-mod asset_manager;
-mod components;
-mod resources;
-mod sound;
-mod systems;
-mod ui;
-
-use crate::{
-    resources::{Camera, DeltaTime, GameMode, GameState},
+use bevy_ecs::{message::MessageRegistry, schedule::Schedule, world::World};
+use flipper::{
+    asset_manager::AssetManager,
+    collision::{CollisionEnded, CollisionStarted, Contacts},
+    controls::{Action, Controls, Gamepads},
+    daily::{DAILY_FILE_NAME, DailyChallenge},
+    display::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH, VirtualScreen},
+    level::{Campaign, CurrentLevel, Level},
+    locale::Strings,
+    menu::{MenuConfirmed, MenuNavigation},
+    preferences::{PREFERENCES_FILE_NAME, Preferences},
+    progress::{PROGRESS_FILE_NAME, Progress},
+    resources::{
+        Camera, CourseIndex, DeltaTime, Difficulty, Display, GameAssets, GameMode, GameState,
+        LivesSettings, PlayMode, PracticeSettings, RaceResult, TwoPlayerSettings,
+    },
+    state::apply_queued_transition,
+    storage::save_path,
     systems::{
-        create_exiting_schedule, create_game_over_schedule, create_menu_schedule,
-        create_playing_schedule, create_title_schedule, create_victory_schedule, initialise_fonts,
-        initialise_sound_resources, spawn_entities,
+        FlipperHit, LevelLoaded, create_display_schedule, create_exiting_schedule,
+        create_game_over_draw_schedule, create_game_over_schedule, create_menu_draw_schedule,
+        create_menu_schedule, create_playing_draw_schedule, create_playing_schedule,
+        create_title_draw_schedule, create_title_schedule, create_transition_hooks,
+        create_victory_draw_schedule, create_victory_schedule, initialise_fonts,
+        initialise_sound_resources,
     },
-    ui::{COLUMBIABLUE, DARKPASTELGREEN, DEEPSKYBLUE, MAIZE, YINMNBLUE},
+    theme::Theme,
 };
-use bevy_ecs::{schedule::Schedule, world::World};
 use macroquad::{
-    input::{KeyCode, is_key_down, prevent_quit},
+    input::prevent_quit,
     window::{Conf, clear_background, next_frame},
 };
-use resources::{ClearedObstacles, GameAssets};
-
-const WINDOW_WIDTH: f32 = 800.0;
-const WINDOW_HEIGHT: f32 = 600.0;
 
 fn conf() -> Conf {
     #[allow(clippy::cast_possible_truncation)]
     Conf {
         window_title: String::from("Flipper"),
-        window_width: WINDOW_WIDTH as i32,
-        window_height: WINDOW_HEIGHT as i32,
+        window_width: VIRTUAL_WIDTH as i32,
+        window_height: VIRTUAL_HEIGHT as i32,
+        window_resizable: true,
         ..Default::default()
     }
 }
@@ -43,11 +51,35 @@ async fn main() {
     let mut world = World::new();
     world.init_resource::<DeltaTime>();
     world.init_resource::<Camera>();
+    world.init_resource::<CourseIndex>();
+    world.init_resource::<Display>();
     world.init_resource::<GameState>();
     world.init_resource::<GameAssets>();
-    world.init_resource::<ClearedObstacles>();
-
-    spawn_entities(&mut world).await;
+    world.init_resource::<Level>();
+    world.init_resource::<Contacts>();
+    world.init_resource::<LivesSettings>();
+    world.init_resource::<TwoPlayerSettings>();
+    world.init_resource::<RaceResult>();
+    world.init_resource::<Difficulty>();
+    world.init_resource::<PlayMode>();
+    world.init_resource::<PracticeSettings>();
+    world.init_resource::<Campaign>();
+    world.init_resource::<CurrentLevel>();
+    world.init_resource::<MenuNavigation>();
+    world.init_resource::<Controls>();
+    world.init_non_send_resource::<Gamepads>();
+    let preferences = Preferences::load(&save_path(PREFERENCES_FILE_NAME));
+    world.insert_resource(Strings::new(preferences.locale));
+    world.insert_resource(Theme::new(preferences.palette));
+    world.insert_resource(preferences);
+    let level_count = world.resource::<Campaign>().levels.len();
+    world.insert_resource(Progress::load(&save_path(PROGRESS_FILE_NAME), level_count));
+    world.insert_resource(DailyChallenge::load(&save_path(DAILY_FILE_NAME)));
+    MessageRegistry::register_message::<CollisionStarted>(&mut world);
+    MessageRegistry::register_message::<CollisionEnded>(&mut world);
+    MessageRegistry::register_message::<FlipperHit>(&mut world);
+    MessageRegistry::register_message::<LevelLoaded>(&mut world);
+    MessageRegistry::register_message::<MenuConfirmed>(&mut world);
 
     let mut initialise_sound_system = Schedule::default();
     initialise_sound_system.add_systems(initialise_sound_resources);
@@ -57,49 +89,74 @@ async fn main() {
     initialise_fonts_system.add_systems(initialise_fonts);
     initialise_fonts_system.run(&mut world);
 
+    let virtual_screen = VirtualScreen::new();
+
+    let mut display_schedule = create_display_schedule();
     let mut exiting_schedule = create_exiting_schedule();
     let mut title_schedule = create_title_schedule();
+    let mut title_draw_schedule = create_title_draw_schedule();
     let mut menu_schedule = create_menu_schedule();
+    let mut menu_draw_schedule = create_menu_draw_schedule();
     let mut playing_schedule = create_playing_schedule();
+    let mut playing_draw_schedule = create_playing_draw_schedule();
     let mut victory_schedule = create_victory_schedule();
+    let mut victory_draw_schedule = create_victory_draw_schedule();
     let mut game_over_schedule = create_game_over_schedule();
+    let mut game_over_draw_schedule = create_game_over_draw_schedule();
+    let mut transition_hooks = create_transition_hooks();
 
     loop {
+        // the font a locale names for its script loads here, between frames, and is swapped in
+        // once ready, so changing language never stalls a system
+        let script_font = world
+            .resource::<Strings>()
+            .script_font()
+            .map(str::to_string);
+        if world.resource::<GameAssets>().fonts.script_path != script_font {
+            let font = AssetManager::load_script_font(script_font.as_deref()).await;
+            let fonts = &mut world.resource_mut::<GameAssets>().fonts;
+            fonts.script = font;
+            fonts.script_path = script_font;
+        }
+
         let game_state = world
             .get_resource::<GameState>()
             .expect("Expected state to have been initialised.");
         //logging::trace!("Game mode is {:?}", game_state.mode);
 
-        match &game_state.mode {
-            GameMode::Exiting(_resume_mode) => {
-                clear_background(MAIZE.into());
-                if is_key_down(KeyCode::Enter) {
-                    break;
-                }
-                exiting_schedule.run(&mut world);
-            }
-            GameMode::Title => {
-                clear_background(MAIZE.into());
-                title_schedule.run(&mut world);
-            }
-            GameMode::Menu => {
-                clear_background(DARKPASTELGREEN.into());
-                menu_schedule.run(&mut world);
-            }
-            GameMode::Playing => {
-                clear_background(DEEPSKYBLUE.into());
-                playing_schedule.run(&mut world);
-            }
-            GameMode::GameOver => {
-                clear_background(COLUMBIABLUE.into());
-                game_over_schedule.run(&mut world);
-            }
-            GameMode::Won => {
-                clear_background(YINMNBLUE.into());
-                victory_schedule.run(&mut world);
+        // An overlay freezes the screen beneath it: that screen is still drawn, but not updated.
+        let overlay = game_state.mode().is_overlay();
+        let background = world
+            .resource::<Theme>()
+            .screen(game_state.mode().screen())
+            .background;
+        let (schedule, draw_schedule) = match game_state.mode().screen() {
+            GameMode::Exiting(_) => unreachable!("overlays always sit over a screen"),
+            GameMode::Title => (&mut title_schedule, &mut title_draw_schedule),
+            GameMode::Menu => (&mut menu_schedule, &mut menu_draw_schedule),
+            GameMode::Playing => (&mut playing_schedule, &mut playing_draw_schedule),
+            GameMode::GameOver => (&mut game_over_schedule, &mut game_over_draw_schedule),
+            GameMode::Won => (&mut victory_schedule, &mut victory_draw_schedule),
+        };
+
+        virtual_screen.begin();
+        clear_background(background.into());
+        if !overlay {
+            schedule.run(&mut world);
+        }
+        draw_schedule.run(&mut world);
+        if overlay {
+            if world.resource::<Controls>().is_pressed(Action::Confirm) {
+                break;
             }
+            exiting_schedule.run(&mut world);
         }
 
+        let letterbox = virtual_screen.present();
+        world.resource_mut::<Display>().letterbox = letterbox;
+        display_schedule.run(&mut world);
+        apply_queued_transition(&mut world, &mut transition_hooks);
+
         next_frame().await;
     }
 }
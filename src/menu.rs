@@ -0,0 +1,308 @@
+use bevy_ecs::{message::Message, resource::Resource};
+use macroquad::math::{Rect, Vec2};
+
+use crate::resources::PlayMode;
+
+/// Pages within the menu, each a list of focusable items.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MenuPage {
+    #[default]
+    Main,
+    LevelSelect,
+    Settings,
+    HighScores,
+    Credits,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuItem {
+    Play,
+    LevelSelect,
+    Settings,
+    HighScores,
+    Credits,
+    Quit,
+    Level(usize),
+    PlayMode,
+    Difficulty,
+    Lives,
+    Players,
+    Language,
+    Palette,
+    PracticeStart,
+    PracticeSpeed,
+    Back,
+}
+
+impl MenuPage {
+    /// Focusable items on this page, top to bottom.  The daily challenge has no level to pick,
+    /// and practice options only show for practice runs.
+    pub fn items(self, level_count: usize, play_mode: PlayMode) -> Vec<MenuItem> {
+        let mut result = match self {
+            MenuPage::Main => {
+                return vec![
+                    MenuItem::Play,
+                    MenuItem::LevelSelect,
+                    MenuItem::Settings,
+                    MenuItem::HighScores,
+                    MenuItem::Credits,
+                    MenuItem::Quit,
+                ];
+            }
+            MenuPage::LevelSelect if play_mode == PlayMode::Daily => Vec::new(),
+            MenuPage::LevelSelect => (0..level_count).map(MenuItem::Level).collect(),
+            MenuPage::Settings if play_mode == PlayMode::Practice => vec![
+                MenuItem::PlayMode,
+                MenuItem::Difficulty,
+                MenuItem::Lives,
+                MenuItem::Players,
+                MenuItem::Language,
+                MenuItem::Palette,
+                MenuItem::PracticeStart,
+                MenuItem::PracticeSpeed,
+            ],
+            MenuPage::Settings => vec![
+                MenuItem::PlayMode,
+                MenuItem::Difficulty,
+                MenuItem::Lives,
+                MenuItem::Players,
+                MenuItem::Language,
+                MenuItem::Palette,
+            ],
+            MenuPage::HighScores | MenuPage::Credits => Vec::new(),
+        };
+        result.push(MenuItem::Back);
+
+        result
+    }
+
+    /// Main menu item which opens this page.
+    fn opened_by(self) -> Option<MenuItem> {
+        match self {
+            MenuPage::Main => None,
+            MenuPage::LevelSelect => Some(MenuItem::LevelSelect),
+            MenuPage::Settings => Some(MenuItem::Settings),
+            MenuPage::HighScores => Some(MenuItem::HighScores),
+            MenuPage::Credits => Some(MenuItem::Credits),
+        }
+    }
+}
+
+/// Navigation input, whichever device it came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuInput {
+    Up,
+    Down,
+
+    /// Pointer moved over the item at this position on the page.
+    Hover(usize),
+    Confirm,
+    Back,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MenuOutcome {
+    Idle,
+    FocusMoved,
+    PageChanged,
+
+    /// Item with an effect outside the menu, for other systems to act on.
+    Confirmed(MenuItem),
+
+    /// Player asked to leave the game from the main page.
+    Quit,
+}
+
+/// Written when the player confirms a menu item which acts outside the menu itself.
+#[derive(Debug, Message)]
+pub struct MenuConfirmed {
+    pub item: MenuItem,
+}
+
+/// Menu page on show and its focused item.
+#[derive(Debug, Default, Resource)]
+pub struct MenuNavigation {
+    pub page: MenuPage,
+    pub focus: usize,
+
+    /// Where each item on the page was last drawn, in item order, in virtual screen
+    /// coordinates; the mouse picks items from these.
+    pub item_areas: Vec<Rect>,
+}
+
+impl MenuNavigation {
+    /// Position of the item under `point`, in virtual screen coordinates.
+    #[must_use]
+    pub fn item_at(&self, point: Vec2) -> Option<usize> {
+        self.item_areas.iter().position(|area| area.contains(point))
+    }
+
+    fn open(&mut self, page: MenuPage, focus: usize) {
+        self.page = page;
+        self.focus = focus;
+        self.item_areas.clear();
+    }
+
+    /// Returns to the main page, focusing the item which opened the page being left.
+    fn back_to_main(&mut self) {
+        let main_items = MenuPage::Main.items(0, PlayMode::Standard);
+        let focus = self
+            .page
+            .opened_by()
+            .and_then(|item| main_items.iter().position(|value| *value == item))
+            .unwrap_or_default();
+        self.open(MenuPage::Main, focus);
+    }
+
+    /// Applies one input to the page, whose focusable `items` are passed in.  Up and down wrap
+    /// around the page.
+    pub fn navigate(&mut self, input: MenuInput, items: &[MenuItem]) -> MenuOutcome {
+        let count = items.len();
+        match input {
+            MenuInput::Up | MenuInput::Down if count == 0 => MenuOutcome::Idle,
+            MenuInput::Up => {
+                self.focus = (self.focus + count - 1) % count;
+                MenuOutcome::FocusMoved
+            }
+            MenuInput::Down => {
+                self.focus = (self.focus + 1) % count;
+                MenuOutcome::FocusMoved
+            }
+            MenuInput::Hover(index) if index != self.focus && index < count => {
+                self.focus = index;
+                MenuOutcome::FocusMoved
+            }
+            MenuInput::Hover(_) => MenuOutcome::Idle,
+            MenuInput::Confirm => match items.get(self.focus) {
+                Some(MenuItem::LevelSelect) => {
+                    self.open(MenuPage::LevelSelect, 0);
+                    MenuOutcome::PageChanged
+                }
+                Some(MenuItem::Settings) => {
+                    self.open(MenuPage::Settings, 0);
+                    MenuOutcome::PageChanged
+                }
+                Some(MenuItem::HighScores) => {
+                    self.open(MenuPage::HighScores, 0);
+                    MenuOutcome::PageChanged
+                }
+                Some(MenuItem::Credits) => {
+                    self.open(MenuPage::Credits, 0);
+                    MenuOutcome::PageChanged
+                }
+                Some(MenuItem::Back) => {
+                    self.back_to_main();
+                    MenuOutcome::PageChanged
+                }
+                Some(MenuItem::Quit) => MenuOutcome::Quit,
+                Some(item) => MenuOutcome::Confirmed(*item),
+                None => MenuOutcome::Idle,
+            },
+            MenuInput::Back if self.page == MenuPage::Main => MenuOutcome::Quit,
+            MenuInput::Back => {
+                self.back_to_main();
+                MenuOutcome::PageChanged
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MenuInput, MenuItem, MenuNavigation, MenuOutcome, MenuPage};
+    use crate::resources::PlayMode;
+    use macroquad::math::{Rect, vec2};
+
+    #[test]
+    fn navigate_wraps_focus_around_page() {
+        // arrange
+        let mut menu = MenuNavigation::default();
+        let items = MenuPage::Main.items(3, PlayMode::Standard);
+
+        // act
+        let outcome = menu.navigate(MenuInput::Up, &items);
+
+        // assert
+        assert_eq!(outcome, MenuOutcome::FocusMoved);
+        assert_eq!(items[menu.focus], MenuItem::Quit);
+    }
+
+    #[test]
+    fn navigate_back_returns_to_item_which_opened_page() {
+        // arrange
+        let mut menu = MenuNavigation::default();
+        let main_items = MenuPage::Main.items(3, PlayMode::Standard);
+        menu.navigate(MenuInput::Hover(3), &main_items);
+        menu.navigate(MenuInput::Confirm, &main_items);
+
+        // act
+        let opened = menu.page;
+        let outcome = menu.navigate(
+            MenuInput::Back,
+            &MenuPage::HighScores.items(3, PlayMode::Standard),
+        );
+
+        // assert
+        assert_eq!(opened, MenuPage::HighScores);
+        assert_eq!(outcome, MenuOutcome::PageChanged);
+        assert_eq!(menu.page, MenuPage::Main);
+        assert_eq!(main_items[menu.focus], MenuItem::HighScores);
+    }
+
+    #[test]
+    fn navigate_hands_off_actions_and_quits_from_main_page() {
+        // arrange
+        let mut menu = MenuNavigation::default();
+        let items = MenuPage::Main.items(3, PlayMode::Standard);
+
+        // act
+        let play = menu.navigate(MenuInput::Confirm, &items);
+        let back = menu.navigate(MenuInput::Back, &items);
+
+        // assert
+        assert_eq!(play, MenuOutcome::Confirmed(MenuItem::Play));
+        assert_eq!(back, MenuOutcome::Quit);
+    }
+
+    #[test]
+    fn items_list_levels_then_back() {
+        // arrange
+        let level_count = 2;
+
+        // act
+        let result = [
+            MenuPage::LevelSelect.items(level_count, PlayMode::TimeTrial),
+            MenuPage::LevelSelect.items(level_count, PlayMode::Daily),
+        ];
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                vec![MenuItem::Level(0), MenuItem::Level(1), MenuItem::Back],
+                vec![MenuItem::Back],
+            ]
+        );
+    }
+
+    #[test]
+    fn item_at_finds_item_drawn_under_point() {
+        // arrange
+        let menu = MenuNavigation {
+            item_areas: vec![
+                Rect::new(100.0, 100.0, 200.0, 40.0),
+                Rect::new(100.0, 150.0, 200.0, 40.0),
+            ],
+            ..Default::default()
+        };
+
+        // act
+        let result = [
+            menu.item_at(vec2(150.0, 170.0)),
+            menu.item_at(vec2(50.0, 170.0)),
+        ];
+
+        // assert
+        assert_eq!(result, [Some(1), None]);
+    }
+}
@@ -0,0 +1,108 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use bevy_ecs::resource::Resource;
+use macroquad::logging::{error, info};
+
+use crate::{locale::Locale, theme::Palette};
+
+/// Choices from the settings page kept between sessions, in a plain text file in the
+/// per-user data directory with one `name = value` line each.
+pub const PREFERENCES_FILE_NAME: &str = "preferences.txt";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct Preferences {
+    pub locale: Locale,
+    pub palette: Palette,
+}
+
+impl Preferences {
+    /// Parses saved preferences.  Unknown names and unreadable values keep their defaults.
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut result = Preferences::default();
+        for line in text.lines() {
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            match (name.trim(), value.trim()) {
+                ("locale", value) => {
+                    if let Some(locale) = Locale::from_code(value) {
+                        result.locale = locale;
+                    }
+                }
+                ("palette", value) => {
+                    if let Some(palette) = Palette::from_code(value) {
+                        result.palette = palette;
+                    }
+                }
+                (name, _) => info!("Ignoring unknown preference `{name}`"),
+            }
+        }
+
+        result
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut result = String::new();
+        let _ = writeln!(result, "locale = {}", self.locale.code());
+        let _ = writeln!(result, "palette = {}", self.palette.code());
+
+        result
+    }
+
+    /// Loads preferences from `path`, keeping the defaults if there is no saved file yet.
+    #[must_use]
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(value) => Preferences::from_text(&value),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                info!("No saved preferences found");
+                Preferences::default()
+            }
+            Err(error) => {
+                error!("Unable to read saved preferences: {error}");
+                Preferences::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) {
+        if let Err(error) = fs::write(path, self.to_text()) {
+            error!("Unable to save preferences: {error}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Preferences;
+    use crate::{locale::Locale, theme::Palette};
+
+    #[test]
+    fn preferences_text_round_trips() {
+        // arrange
+        let preferences = Preferences {
+            locale: Locale::French,
+            palette: Palette::HighContrast,
+        };
+
+        // act
+        let result = Preferences::from_text(&preferences.to_text());
+
+        // assert
+        assert_eq!(result, preferences);
+    }
+
+    #[test]
+    fn from_text_keeps_defaults_for_unreadable_values() {
+        // arrange
+        let text = "locale = xx\npalette = sepia\ncolour = blue\n";
+
+        // act
+        let result = Preferences::from_text(text);
+
+        // assert
+        assert_eq!(result, Preferences::default());
+    }
+}
@@ -0,0 +1,281 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use bevy_ecs::resource::Resource;
+use macroquad::logging::{error, info};
+
+use crate::resources::Difficulty;
+
+/// Campaign progress is kept in a plain text file in the per-user data directory: a scoring
+/// header, then one line per level holding the completed flag then the best score for each
+/// difficulty.
+pub const PROGRESS_FILE_NAME: &str = "progress.txt";
+
+/// Version of the scoring rules saved scores were set under, written as a `scoring` header on
+/// saved files.  Bump it whenever scoring changes so that old scores no longer compare; the
+/// combo multiplier was the change to version 2.  Files without the header predate version 2.
+pub const SCORING_VERSION: u32 = 2;
+
+/// Splits the `scoring` header off saved text, returning whether scores in the rest were set
+/// under the current scoring rules.
+#[must_use]
+pub fn split_scoring_header(text: &str) -> (bool, &str) {
+    let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+    match first.trim().strip_prefix("scoring ") {
+        Some(version) => (version.trim().parse() == Ok(SCORING_VERSION), rest),
+        None => (false, text),
+    }
+}
+
+#[must_use]
+pub fn scoring_header() -> String {
+    format!("scoring {SCORING_VERSION}\n")
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LevelStatus {
+    Locked,
+    Unlocked,
+    Completed,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LevelProgress {
+    pub completed: bool,
+
+    /// Indexed by `Difficulty::index`, so scores are only compared within a difficulty.
+    pub best_scores: [Option<u32>; Difficulty::ALL.len()],
+}
+
+impl LevelProgress {
+    #[must_use]
+    pub fn best_score(&self, difficulty: Difficulty) -> Option<u32> {
+        self.best_scores[difficulty.index()]
+    }
+}
+
+/// Completion and per-difficulty best scores for each campaign level, in campaign order.
+#[derive(Clone, Debug, PartialEq, Resource)]
+pub struct Progress {
+    pub levels: Vec<LevelProgress>,
+}
+
+impl Progress {
+    #[must_use]
+    pub fn new(level_count: usize) -> Self {
+        Progress {
+            levels: vec![LevelProgress::default(); level_count],
+        }
+    }
+
+    /// The first level is always open; each later level unlocks once the one before it is
+    /// completed.
+    #[must_use]
+    pub fn status(&self, index: usize) -> LevelStatus {
+        match self.levels.get(index) {
+            Some(LevelProgress {
+                completed: true, ..
+            }) => LevelStatus::Completed,
+            Some(_) if index == 0 || self.levels[index - 1].completed => LevelStatus::Unlocked,
+            _ => LevelStatus::Locked,
+        }
+    }
+
+    /// Records a finished run, returning `true` if it set a new best score for the level at
+    /// this difficulty.
+    pub fn record(
+        &mut self,
+        index: usize,
+        difficulty: Difficulty,
+        score: u32,
+        completed: bool,
+    ) -> bool {
+        let Some(level) = self.levels.get_mut(index) else {
+            return false;
+        };
+        level.completed |= completed;
+        let best_score = &mut level.best_scores[difficulty.index()];
+        if best_score.is_none_or(|best| score > best) {
+            *best_score = Some(score);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses saved progress, padding or truncating to `level_count` levels so a changed
+    /// campaign still loads.  Unreadable lines count as no progress, and best scores set under
+    /// other scoring rules are dropped, keeping completion.
+    #[must_use]
+    pub fn from_text(text: &str, level_count: usize) -> Self {
+        let (current_scoring, text) = split_scoring_header(text);
+        let mut result = Progress::new(level_count);
+        for (level, line) in result.levels.iter_mut().zip(text.lines()) {
+            let mut fields = line.split_whitespace();
+            level.completed = fields.next() == Some("1");
+            if !current_scoring {
+                continue;
+            }
+            for (best_score, field) in level.best_scores.iter_mut().zip(fields) {
+                *best_score = field.parse().ok();
+            }
+        }
+
+        result
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut result = scoring_header();
+        for LevelProgress {
+            completed,
+            best_scores,
+        } in &self.levels
+        {
+            let best_scores = best_scores
+                .iter()
+                .map(|value| value.map_or_else(|| String::from("-"), |value| value.to_string()))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(result, "{} {best_scores}", u8::from(*completed));
+        }
+
+        result
+    }
+
+    /// Loads progress from `path`, starting afresh if there is no saved file yet.
+    #[must_use]
+    pub fn load(path: &Path, level_count: usize) -> Self {
+        match fs::read_to_string(path) {
+            Ok(value) => Progress::from_text(&value, level_count),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                info!("No saved progress found, starting a new campaign");
+                Progress::new(level_count)
+            }
+            Err(error) => {
+                error!("Unable to read saved progress: {error}");
+                Progress::new(level_count)
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) {
+        if let Err(error) = fs::write(path, self.to_text()) {
+            error!("Unable to save progress: {error}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LevelProgress, LevelStatus, Progress, SCORING_VERSION};
+    use crate::resources::Difficulty;
+
+    #[test]
+    fn status_unlocks_level_after_completed_level() {
+        // arrange
+        let mut progress = Progress::new(3);
+        progress.levels[0].completed = true;
+
+        // act
+        let result = [progress.status(0), progress.status(1), progress.status(2)];
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                LevelStatus::Completed,
+                LevelStatus::Unlocked,
+                LevelStatus::Locked
+            ]
+        );
+    }
+
+    #[test]
+    fn record_keeps_best_score_and_completion() {
+        // arrange
+        let mut progress = Progress::new(1);
+        progress.record(0, Difficulty::Normal, 10, true);
+
+        // act
+        let result = progress.record(0, Difficulty::Normal, 7, false);
+
+        // assert
+        assert!(!result);
+        assert_eq!(
+            progress.levels[0],
+            LevelProgress {
+                completed: true,
+                best_scores: [None, Some(10), None]
+            }
+        );
+    }
+
+    #[test]
+    fn record_keeps_best_scores_per_difficulty() {
+        // arrange
+        let mut progress = Progress::new(1);
+        progress.record(0, Difficulty::Easy, 20, true);
+
+        // act
+        let result = progress.record(0, Difficulty::Hard, 5, false);
+
+        // assert
+        assert!(result);
+        assert_eq!(progress.levels[0].best_score(Difficulty::Easy), Some(20));
+        assert_eq!(progress.levels[0].best_score(Difficulty::Normal), None);
+        assert_eq!(progress.levels[0].best_score(Difficulty::Hard), Some(5));
+    }
+
+    #[test]
+    fn progress_text_round_trips() {
+        // arrange
+        let mut progress = Progress::new(3);
+        progress.record(0, Difficulty::Normal, 12, true);
+        progress.record(0, Difficulty::Hard, 3, false);
+        progress.record(1, Difficulty::Easy, 4, false);
+
+        // act
+        let result = Progress::from_text(&progress.to_text(), 3);
+
+        // assert
+        assert_eq!(result, progress);
+    }
+
+    #[test]
+    fn from_text_pads_missing_levels() {
+        // arrange
+        let text = format!("scoring {SCORING_VERSION}\n1 - 8 -\n");
+
+        // act
+        let result = Progress::from_text(&text, 2);
+
+        // assert
+        assert_eq!(result.levels.len(), 2);
+        assert_eq!(result.levels[0].best_score(Difficulty::Normal), Some(8));
+        assert_eq!(result.levels[1], LevelProgress::default());
+    }
+
+    #[test]
+    fn from_text_drops_best_scores_set_under_other_scoring_rules() {
+        // arrange
+        let legacy = "1 - 8 -\n0 4 - -\n";
+        let older = "scoring 1\n1 - 8 -\n0 4 - -\n";
+
+        // act
+        let result = [legacy, older].map(|text| Progress::from_text(text, 2));
+
+        // assert
+        for progress in result {
+            assert_eq!(
+                progress.levels,
+                [
+                    LevelProgress {
+                        completed: true,
+                        best_scores: [None; 3]
+                    },
+                    LevelProgress::default()
+                ]
+            );
+        }
+    }
+}
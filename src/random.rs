@@ -0,0 +1,70 @@
+/// Small seeded pseudo-random generator (`SplitMix64`), so a seed always produces the same
+/// sequence on every machine.
+#[derive(Clone, Debug)]
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        SeededRandom { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut result = self.state;
+        result = (result ^ (result >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        result = (result ^ (result >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        result ^ (result >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        // top 24 bits fill the f32 mantissa exactly
+        #[allow(clippy::cast_precision_loss)]
+        let result = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        result
+    }
+
+    /// Uniform value in `[min, max)`.
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// True with the given probability.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeededRandom;
+
+    #[test]
+    fn seeded_random_repeats_sequence_for_seed() {
+        // arrange
+        let mut first = SeededRandom::new(20_261_018);
+        let mut second = SeededRandom::new(20_261_018);
+
+        // act
+        let result: Vec<u64> = (0..4).map(|_| first.next_u64()).collect();
+        let expected: Vec<u64> = (0..4).map(|_| second.next_u64()).collect();
+
+        // assert
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn seeded_random_range_stays_in_bounds() {
+        // arrange
+        let mut random = SeededRandom::new(7);
+
+        // act
+        let result: Vec<f32> = (0..1_000).map(|_| random.range(60.0, 90.0)).collect();
+
+        // assert
+        assert!(result.iter().all(|value| (60.0..90.0).contains(value)));
+    }
+}
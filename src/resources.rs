@@ -1,17 +1,23 @@
-use bevy_ecs::{entity::Entity, resource::Resource};
-use macroquad::{audio::Sound, text::Font};
+use std::collections::VecDeque;
 
-use crate::WINDOW_WIDTH;
+use bevy_ecs::{entity::Entity, resource::Resource};
+use macroquad::{
+    audio::Sound,
+    logging::{error, warn},
+    text::Font,
+};
 
-#[derive(Debug, PartialEq, Resource)]
-pub enum ResumeGameMode {
-    Menu,
-    Playing,
-}
+use crate::{
+    components::{HorizontalExtent, Position},
+    display::{Letterbox, VIRTUAL_WIDTH},
+};
 
-#[derive(Debug, Default, PartialEq, Resource)]
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
 pub enum GameMode {
-    Exiting(ResumeGameMode),
+    /// Quit confirmation, drawn over the mode it was opened from, which is held here so closing
+    /// the confirmation returns to exactly that mode.
+    Exiting(Box<GameMode>),
+
     Menu,
     Playing,
     GameOver,
@@ -22,9 +28,114 @@ pub enum GameMode {
     Won,
 }
 
+impl GameMode {
+    /// Overlays stack on top of another mode, which stays on screen beneath them.
+    #[must_use]
+    pub fn is_overlay(&self) -> bool {
+        matches!(self, GameMode::Exiting(_))
+    }
+
+    /// Mode whose screen is drawn: the mode itself, or for an overlay, the mode beneath it.
+    #[must_use]
+    pub fn screen(&self) -> &GameMode {
+        match self {
+            GameMode::Exiting(beneath) => beneath.screen(),
+            _ => self,
+        }
+    }
+
+    /// Whether the game may move straight from this mode to `next`.  An overlay opens over any
+    /// mode except another overlay and only closes back to that mode, and no mode transitions
+    /// to itself.
+    #[must_use]
+    pub fn can_transition_to(&self, next: &GameMode) -> bool {
+        match (self, next) {
+            (GameMode::Exiting(beneath), _) => **beneath == *next,
+            (_, GameMode::Exiting(beneath)) => **beneath == *self,
+            _ => matches!(
+                (self, next),
+                (
+                    GameMode::Title | GameMode::GameOver | GameMode::Won,
+                    GameMode::Menu
+                ) | (GameMode::Menu, GameMode::Playing)
+                    | (GameMode::Playing, GameMode::GameOver | GameMode::Won)
+            ),
+        }
+    }
+}
+
+/// Current game mode, with any transition systems have queued this frame.  Transitions are
+/// applied between frames, running the exit hooks for the old mode then the enter hooks for the
+/// new one.
 #[derive(Debug, Default, Resource)]
 pub struct GameState {
-    pub mode: GameMode,
+    mode: GameMode,
+    next_mode: Option<GameMode>,
+}
+
+impl GameState {
+    #[must_use]
+    pub fn mode(&self) -> &GameMode {
+        &self.mode
+    }
+
+    /// Transition queued for the end of this frame.
+    #[must_use]
+    pub fn next_mode(&self) -> Option<&GameMode> {
+        self.next_mode.as_ref()
+    }
+
+    /// Queues a transition to `next`.  Illegal transitions are logged and dropped, and once one
+    /// transition is queued for the frame, later requests are ignored.
+    pub fn queue(&mut self, next: GameMode) {
+        if !self.mode.can_transition_to(&next) {
+            error!(
+                "Ignoring illegal game mode transition: {:?} -> {next:?}",
+                self.mode
+            );
+        } else if let Some(queued) = &self.next_mode {
+            warn!("Ignoring transition to {next:?}, already heading to {queued:?}");
+        } else {
+            self.next_mode = Some(next);
+        }
+    }
+
+    /// Moves to the queued mode, if any, returning the mode left and the mode entered.
+    pub fn apply_queued(&mut self) -> Option<(GameMode, GameMode)> {
+        let next = self.next_mode.take()?;
+        let previous = std::mem::replace(&mut self.mode, next.clone());
+
+        Some((previous, next))
+    }
+}
+
+/// Player choice, made from the menu, of whether a hit costs a life rather than ending the run.
+#[derive(Debug, Resource)]
+pub struct LivesSettings {
+    pub enabled: bool,
+    pub lives: u32,
+}
+
+impl Default for LivesSettings {
+    fn default() -> Self {
+        LivesSettings {
+            enabled: false,
+            lives: 3,
+        }
+    }
+}
+
+/// Player choice, made from the menu, of racing a second local player on the same keyboard.
+#[derive(Debug, Default, Resource)]
+pub struct TwoPlayerSettings {
+    pub enabled: bool,
+}
+
+/// Outcome of the last two-player race, for the results screen.
+#[derive(Debug, Default, Resource)]
+pub struct RaceResult {
+    /// Number of the winning player, or `None` for a draw.
+    pub winner: Option<u8>,
 }
 
 #[derive(Default, Resource)]
@@ -32,6 +143,12 @@ pub struct GameFonts {
     pub body: Option<Font>,
     pub body_italic: Option<Font>,
     pub heading: Option<Font>,
+
+    /// Font for the locale's script, drawn in place of every face above when set.
+    pub script: Option<Font>,
+
+    /// Where `script` was loaded from, so the main loop can tell when the locale wants another.
+    pub script_path: Option<String>,
 }
 
 #[derive(Default, Resource)]
@@ -40,6 +157,7 @@ pub struct GameSounds {
     pub flap: Option<Sound>,
     pub game_over: Option<Sound>,
     pub obstacle_cleared: Option<Sound>,
+    pub pickup: Option<Sound>,
     pub victory: Option<Sound>,
 }
 
@@ -49,25 +167,194 @@ pub struct GameAssets {
     pub sounds: GameSounds,
 }
 
+/// How runs started from the menu are played.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Resource)]
+pub enum PlayMode {
+    #[default]
+    Standard,
+
+    /// Timed run with splits and medals, where the player controls horizontal speed.
+    TimeTrial,
+
+    /// Procedural course seeded from the UTC date, the same for every player on a given day.
+    Daily,
+
+    /// Rehearsal of the selected level from any obstacle, optionally slowed down, which never
+    /// counts towards saved scores.
+    Practice,
+}
+
+impl PlayMode {
+    pub const ALL: [PlayMode; 4] = [
+        PlayMode::Standard,
+        PlayMode::TimeTrial,
+        PlayMode::Daily,
+        PlayMode::Practice,
+    ];
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        let index = PlayMode::ALL
+            .iter()
+            .position(|value| *value == self)
+            .unwrap_or_default();
+        PlayMode::ALL[(index + 1) % PlayMode::ALL.len()]
+    }
+
+    /// Id of the mode's name in the string table.
+    #[must_use]
+    pub fn message_id(self) -> &'static str {
+        match self {
+            PlayMode::Standard => "play-mode-standard",
+            PlayMode::TimeTrial => "play-mode-time-trial",
+            PlayMode::Daily => "play-mode-daily",
+            PlayMode::Practice => "play-mode-practice",
+        }
+    }
+}
+
+/// Global time scales practice runs cycle through.
+pub const PRACTICE_TIME_SCALES: [f32; 3] = [1.0, 0.75, 0.5];
+
+/// Practice run options, picked from the menu.
+#[derive(Debug, Default, Resource)]
+pub struct PracticeSettings {
+    /// Obstacle to start just before, counting from one; zero starts from the beginning.
+    pub start_obstacle: usize,
+
+    /// Index into `PRACTICE_TIME_SCALES`.
+    pub time_scale_index: usize,
+}
+
+impl PracticeSettings {
+    #[must_use]
+    pub fn time_scale(&self) -> f32 {
+        PRACTICE_TIME_SCALES[self.time_scale_index % PRACTICE_TIME_SCALES.len()]
+    }
+
+    pub fn cycle_time_scale(&mut self) {
+        self.time_scale_index = (self.time_scale_index + 1) % PRACTICE_TIME_SCALES.len();
+    }
+}
+
+/// Horizontal speed, at normal difficulty, of both the camera and the flipper.
+pub const BASE_PAN_SPEED: f32 = 240.0;
+
+/// Preset chosen from the menu, scaling physics and gap sizes.  High scores are kept separately
+/// for each preset.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Resource)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// Position in `ALL`, used to index per-difficulty records.
+    #[must_use]
+    pub fn index(self) -> usize {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        Difficulty::ALL[(self.index() + 1) % Difficulty::ALL.len()]
+    }
+
+    /// Id of the preset's name in the string table.
+    #[must_use]
+    pub fn message_id(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty-easy",
+            Difficulty::Normal => "difficulty-normal",
+            Difficulty::Hard => "difficulty-hard",
+        }
+    }
+
+    /// Scales the camera pan speed and flipper horizontal velocity together.
+    #[must_use]
+    pub fn speed_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.85,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.15,
+        }
+    }
+
+    /// Downward acceleration of the flipper, in pixels per second per second of game time.
+    #[must_use]
+    pub fn gravity(self) -> f32 {
+        match self {
+            Difficulty::Easy => 300.0,
+            Difficulty::Normal => 360.0,
+            Difficulty::Hard => 420.0,
+        }
+    }
+
+    /// Upward acceleration of the flipper while the flap key is held, in pixels per second per
+    /// second of game time.
+    #[must_use]
+    pub fn flap_strength(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1_620.0,
+            Difficulty::Normal => 1_800.0,
+            Difficulty::Hard => 1_980.0,
+        }
+    }
+
+    /// Scales obstacle gap lengths when a level is spawned.
+    #[must_use]
+    pub fn gap_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.25,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.85,
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct Camera {
     pub pan_speed: f32,
     pub left_displacement: f32,
+
+    /// Extra distance either side of the screen still treated as in view.
+    pub view_margin: f32,
 }
 
 impl Default for Camera {
     fn default() -> Self {
         Camera {
-            pan_speed: 240.0,
+            pan_speed: BASE_PAN_SPEED,
             left_displacement: 0.0,
+            view_margin: 20.0,
         }
     }
 }
 
 impl Camera {
-    pub fn in_view(&self, x_displacement: f32) -> bool {
-        self.left_displacement < x_displacement
-            && x_displacement < self.left_displacement + WINDOW_WIDTH
+    /// Left and right world displacements bounding the visible region, including the margin.
+    #[must_use]
+    pub fn view_bounds(&self) -> (f32, f32) {
+        (
+            self.left_displacement - self.view_margin,
+            self.left_displacement + VIRTUAL_WIDTH + self.view_margin,
+        )
+    }
+
+    /// True when any part of the shape overlaps the visible region.
+    pub fn in_view<T: HorizontalExtent>(&self, shape: &T, position: &Position) -> bool {
+        let (left, right) = shape.horizontal_extent(position);
+        let (view_left, view_right) = self.view_bounds();
+
+        right > view_left && left < view_right
     }
 
     pub fn update(&mut self, delta: f32) {
@@ -75,12 +362,251 @@ impl Camera {
     }
 }
 
+/// Horizontal span of an entity placed along the course, widened to cover any scripted motion.
+#[derive(Clone, Copy, Debug)]
+struct CourseEntry {
+    entity: Entity,
+    left: f32,
+    right: f32,
+}
+
+/// Entities placed along the course, sorted by their left edge, so those near the camera can be
+/// found by binary search however long the course.  Lookups return every entity which might be
+/// in view; callers still check each against `Camera::in_view`.
+#[derive(Debug, Default, Resource)]
+pub struct CourseIndex {
+    entries: VecDeque<CourseEntry>,
+
+    /// Widest span indexed, bounding how far before a window an overlapping entity can start.
+    widest: f32,
+}
+
+impl CourseIndex {
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.widest = 0.0;
+    }
+
+    /// Records an entity spanning `left` to `right`, keeping the entries sorted.
+    pub fn insert(&mut self, entity: Entity, left: f32, right: f32) {
+        let index = self.entries.partition_point(|entry| entry.left <= left);
+        self.entries.insert(
+            index,
+            CourseEntry {
+                entity,
+                left,
+                right,
+            },
+        );
+        self.widest = self.widest.max(right - left);
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes the entities lying wholly left of `x` from the front of the index, returning them.
+    /// An entity reaching past `x` holds back those after it until it goes too.
+    pub fn drain_behind(&mut self, x: f32) -> impl Iterator<Item = Entity> + '_ {
+        let count = self
+            .entries
+            .iter()
+            .take_while(|entry| entry.right < x)
+            .count();
+
+        self.entries.drain(..count).map(|entry| entry.entity)
+    }
+
+    /// Entities which might be within the camera's view bounds.
+    pub fn near_view(&self, camera: &Camera) -> impl Iterator<Item = Entity> + '_ {
+        let (view_left, view_right) = camera.view_bounds();
+        let start = self
+            .entries
+            .partition_point(|entry| entry.left <= view_left - self.widest);
+        let end = self
+            .entries
+            .partition_point(|entry| entry.left < view_right)
+            .max(start);
+
+        self.entries
+            .range(start..end)
+            .filter(move |entry| entry.right > view_left)
+            .map(|entry| entry.entity)
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct Display {
+    pub fullscreen: bool,
+    pub letterbox: Letterbox,
+}
+
 #[derive(Resource, Default)]
 pub struct DeltaTime {
+    /// Frame time with any slow motion applied; use for anything in the game world.
     pub seconds: f32,
+
+    /// Real frame time, for timers which should not slow down with the world.
+    pub unscaled_seconds: f32,
 }
 
-#[derive(Resource, Default)]
-pub struct ClearedObstacles {
-    pub obstacles: Vec<Entity>,
+#[cfg(test)]
+mod tests {
+    use super::{Camera, CourseIndex, GameMode, GameState};
+    use crate::components::{ObstacleShape, Position, RectangleShape};
+    use bevy_ecs::entity::Entity;
+
+    #[test]
+    fn can_transition_to_only_closes_overlay_back_to_mode_beneath() {
+        // arrange
+        let exiting = GameMode::Exiting(Box::new(GameMode::GameOver));
+
+        // act
+        let result = [
+            GameMode::GameOver.can_transition_to(&exiting),
+            exiting.can_transition_to(&GameMode::GameOver),
+            exiting.can_transition_to(&GameMode::Menu),
+            exiting.can_transition_to(&GameMode::Exiting(Box::new(exiting.clone()))),
+            GameMode::Menu.can_transition_to(&exiting),
+            GameMode::Menu.can_transition_to(&GameMode::Menu),
+        ];
+
+        // assert
+        assert_eq!(result, [true, true, false, false, false, false]);
+        assert_eq!(exiting.screen(), &GameMode::GameOver);
+    }
+
+    #[test]
+    fn game_state_queue_keeps_first_legal_transition() {
+        // arrange
+        let mut game_state = GameState::default();
+        game_state.queue(GameMode::Won);
+        game_state.queue(GameMode::Menu);
+        game_state.queue(GameMode::Exiting(Box::new(GameMode::Title)));
+
+        // act
+        let result = game_state.apply_queued();
+
+        // assert
+        assert_eq!(result, Some((GameMode::Title, GameMode::Menu)));
+        assert_eq!(game_state.mode(), &GameMode::Menu);
+        assert_eq!(game_state.next_mode(), None);
+    }
+
+    #[test]
+    fn camera_in_view_includes_shape_partly_off_left_edge() {
+        // arrange
+        let camera = Camera {
+            pan_speed: 240.0,
+            left_displacement: 1000.0,
+            view_margin: 0.0,
+        };
+        let shape = ObstacleShape {
+            gap_length: 100.0,
+            gap_y_displacement: 300.0,
+            width: 15.0,
+        };
+        let position = Position { x: 990.0, y: 0.0 };
+
+        // act
+        let result = camera.in_view(&shape, &position);
+
+        // assert
+        assert!(result);
+    }
+
+    #[test]
+    fn camera_in_view_excludes_shape_fully_off_left_edge() {
+        // arrange
+        let camera = Camera {
+            pan_speed: 240.0,
+            left_displacement: 1000.0,
+            view_margin: 0.0,
+        };
+        let shape = RectangleShape {
+            width: 5.0,
+            height: 600.0,
+        };
+        let position = Position { x: 990.0, y: 0.0 };
+
+        // act
+        let result = camera.in_view(&shape, &position);
+
+        // assert
+        assert!(!result);
+    }
+
+    #[test]
+    fn camera_in_view_includes_shape_within_margin() {
+        // arrange
+        let camera = Camera {
+            pan_speed: 240.0,
+            left_displacement: 1000.0,
+            view_margin: 20.0,
+        };
+        let shape = RectangleShape {
+            width: 5.0,
+            height: 600.0,
+        };
+        let position = Position { x: 1810.0, y: 0.0 };
+
+        // act
+        let result = camera.in_view(&shape, &position);
+
+        // assert
+        assert!(result);
+    }
+
+    #[test]
+    fn course_index_finds_entities_near_view_without_those_far_away() {
+        // arrange
+        let camera = Camera {
+            pan_speed: 240.0,
+            left_displacement: 1000.0,
+            view_margin: 20.0,
+        };
+        let [behind, swinging, inside, edge, ahead] =
+            [1, 2, 3, 4, 5].map(|index| Entity::from_raw_u32(index).unwrap());
+        let mut course_index = CourseIndex::default();
+        // inserted out of order, with an obstacle whose motion reaches in from well behind
+        course_index.insert(ahead, 2_000.0, 2_015.0);
+        course_index.insert(inside, 1_400.0, 1_415.0);
+        course_index.insert(behind, 500.0, 515.0);
+        course_index.insert(edge, 1_810.0, 1_815.0);
+        course_index.insert(swinging, 700.0, 1_015.0);
+
+        // act
+        let result: Vec<Entity> = course_index.near_view(&camera).collect();
+
+        // assert
+        assert_eq!(result, [swinging, inside, edge]);
+        assert_eq!(course_index.len(), 5);
+    }
+
+    #[test]
+    fn drain_behind_removes_leading_entities_wholly_left_of_x() {
+        // arrange
+        let [behind, swinging, passed, ahead] =
+            [1, 2, 3, 4].map(|index| Entity::from_raw_u32(index).unwrap());
+        let mut course_index = CourseIndex::default();
+        course_index.insert(behind, 500.0, 515.0);
+        course_index.insert(swinging, 700.0, 1_015.0);
+        course_index.insert(passed, 800.0, 815.0);
+        course_index.insert(ahead, 2_000.0, 2_015.0);
+
+        // act
+        let first: Vec<Entity> = course_index.drain_behind(900.0).collect();
+        let second: Vec<Entity> = course_index.drain_behind(1_100.0).collect();
+
+        // assert
+        assert_eq!(first, [behind]);
+        assert_eq!(second, [swinging, passed]);
+        assert_eq!(course_index.len(), 1);
+    }
 }
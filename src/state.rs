@@ -0,0 +1,55 @@
+use std::{
+    collections::HashMap,
+    mem::{Discriminant, discriminant},
+};
+
+use bevy_ecs::{schedule::Schedule, world::World};
+use macroquad::logging::info;
+
+use crate::resources::{GameMode, GameState};
+
+/// Schedules run as the game leaves or enters a mode.  Hooks are keyed on the kind of mode, so
+/// the quit prompt has one set, whichever mode it was opened over.
+#[derive(Default)]
+pub struct TransitionHooks {
+    on_enter: HashMap<Discriminant<GameMode>, Schedule>,
+    on_exit: HashMap<Discriminant<GameMode>, Schedule>,
+}
+
+impl TransitionHooks {
+    #[must_use]
+    pub fn on_enter(mut self, mode: &GameMode, schedule: Schedule) -> Self {
+        self.on_enter.insert(discriminant(mode), schedule);
+        self
+    }
+
+    #[must_use]
+    pub fn on_exit(mut self, mode: &GameMode, schedule: Schedule) -> Self {
+        self.on_exit.insert(discriminant(mode), schedule);
+        self
+    }
+}
+
+/// Applies the transition queued during the frame, if any: runs the exit hooks for the current
+/// mode, switches mode, then runs the enter hooks for the new one.  The mode beneath an overlay
+/// is never left, so opening or closing one only runs the overlay's own hooks.  A transition an
+/// enter hook queues waits for the end of the next frame.
+pub fn apply_queued_transition(world: &mut World, hooks: &mut TransitionHooks) {
+    let game_state = world.resource::<GameState>();
+    let Some(next) = game_state.next_mode() else {
+        return;
+    };
+    let current = game_state.mode();
+    let exit_hooks = (!next.is_overlay()).then(|| discriminant(current));
+    let enter_hooks = (!current.is_overlay()).then(|| discriminant(next));
+
+    if let Some(schedule) = exit_hooks.and_then(|key| hooks.on_exit.get_mut(&key)) {
+        schedule.run(world);
+    }
+    if let Some((previous, entered)) = world.resource_mut::<GameState>().apply_queued() {
+        info!("Game mode transition: {previous:?} -> {entered:?}");
+    }
+    if let Some(schedule) = enter_hooks.and_then(|key| hooks.on_enter.get_mut(&key)) {
+        schedule.run(world);
+    }
+}
@@ -0,0 +1,129 @@
+use std::{env, ffi::OsString, fs, path::PathBuf};
+
+use macroquad::logging::error;
+
+/// Name of the directory the game keeps its saves in, inside the platform's per-user data
+/// directory.
+const DATA_DIRECTORY_NAME: &str = "flipper";
+
+/// Path of a save file kept in the per-user data directory, so saves are found whichever
+/// directory the game starts from and even when it is installed read-only.  Creates the directory
+/// if needed, and falls back to the working directory if there is no per-user one.
+#[must_use]
+pub fn save_path(file_name: &str) -> PathBuf {
+    let Some(directory) = data_directory(|key| env::var_os(key)) else {
+        return PathBuf::from(file_name);
+    };
+    if let Err(error) = fs::create_dir_all(&directory) {
+        error!(
+            "Unable to create save directory {}: {error}",
+            directory.display()
+        );
+    }
+    directory.join(file_name)
+}
+
+/// The game's directory inside `$XDG_DATA_HOME`, or `~/.local/share` when that is unset.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn data_directory(var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let base = var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| home_relative(&var, &[".local", "share"]))?;
+    Some(base.join(DATA_DIRECTORY_NAME))
+}
+
+/// The game's directory inside `~/Library/Application Support`.
+#[cfg(target_os = "macos")]
+fn data_directory(var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let base = home_relative(&var, &["Library", "Application Support"])?;
+    Some(base.join(DATA_DIRECTORY_NAME))
+}
+
+/// The game's directory inside `%APPDATA%`.
+#[cfg(windows)]
+fn data_directory(var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let base = var("APPDATA")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())?;
+    Some(base.join(DATA_DIRECTORY_NAME))
+}
+
+/// No per-user data directory is known on other platforms, such as the web.
+#[cfg(not(any(unix, windows)))]
+fn data_directory(_var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(unix)]
+fn home_relative(var: &impl Fn(&str) -> Option<OsString>, components: &[&str]) -> Option<PathBuf> {
+    let home = var("HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())?;
+    Some(
+        components
+            .iter()
+            .fold(home, |path, component| path.join(component)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::data_directory;
+    use std::{ffi::OsString, path::PathBuf};
+
+    fn environment(variables: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let variables: Vec<(String, OsString)> = variables
+            .iter()
+            .map(|(key, value)| ((*key).to_string(), OsString::from(value)))
+            .collect();
+        move |key| {
+            variables
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn data_directory_prefers_xdg_data_home_over_home() {
+        // arrange
+        let var = environment(&[("XDG_DATA_HOME", "/data"), ("HOME", "/home/player")]);
+
+        // act
+        let result = data_directory(var);
+
+        // assert
+        assert_eq!(result, Some(PathBuf::from("/data/flipper")));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn data_directory_falls_back_to_local_share_for_a_relative_xdg_data_home() {
+        // arrange
+        let var = environment(&[("XDG_DATA_HOME", "data"), ("HOME", "/home/player")]);
+
+        // act
+        let result = data_directory(var);
+
+        // assert
+        assert_eq!(
+            result,
+            Some(PathBuf::from("/home/player/.local/share/flipper"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn data_directory_is_none_without_a_home() {
+        // arrange
+        let var = environment(&[]);
+
+        // act
+        let result = data_directory(var);
+
+        // assert
+        assert_eq!(result, None);
+    }
+}
@@ -1,113 +1,506 @@
 use crate::{
-    DeltaTime, WINDOW_HEIGHT, WINDOW_WIDTH,
     asset_manager::AssetManager,
+    collision::{
+        Collider, CollisionStarted, Contacts, detect_collisions, store_previous_positions,
+    },
     components::{
-        Colour, FinishLine, Flipper, ObstacleShape, Position, RectangleShape, Score, Velocity,
+        Checkpoint, ClearedBy, Collectible, CollectibleKind, Colour, Combo, Eliminated, FinishLine,
+        Flipper, Hitbox, HorizontalExtent, Invulnerable, Lives, Magnet, MotionPath, ObstacleMotion,
+        ObstacleShape, PickupEffect, Placed, Player, Position, PowerUp, PowerUpKind,
+        PreviousPosition, RectangleShape, RespawnPoint, RunEntity, Score, Shield, SlowMotion,
+        SpeedControl, TimeTrial, TimedEffect, Velocity,
     },
+    controls::{Action, Controls, Gamepads, InputDevice},
+    daily::{DAILY_FILE_NAME, DailyChallenge, UtcDate},
+    display::{HUD_BAND_HEIGHT, VIRTUAL_HEIGHT},
+    level::{Campaign, CollectibleSpec, CurrentLevel, Level, PowerUpSpec},
+    locale::Strings,
+    menu::{MenuConfirmed, MenuInput, MenuItem, MenuNavigation, MenuOutcome, MenuPage},
+    preferences::{PREFERENCES_FILE_NAME, Preferences},
+    progress::{LevelStatus, PROGRESS_FILE_NAME, Progress},
     resources::{
-        Camera, ClearedObstacles, GameAssets, GameFonts, GameMode, GameState, ResumeGameMode,
+        BASE_PAN_SPEED, Camera, CourseIndex, DeltaTime, Difficulty, Display, GameAssets, GameMode,
+        GameState, LivesSettings, PlayMode, PracticeSettings, RaceResult, TwoPlayerSettings,
     },
     sound::{
         play_sound_once, start_playing_looped as start_playing_looped_sound,
         stop_playing_looped as stop_playing_looped_sound,
     },
+    state::TransitionHooks,
+    storage::save_path,
+    theme::Theme,
     ui::{
-        COLUMBIABLUE, DARKPASTELGREEN, YINMNBLUE, draw_exit_screen_text,
-        draw_game_over_screen_text, draw_info_text, draw_menu_screen_text, draw_title_screen_text,
-        draw_win_screen_text,
+        HudStatus, LevelSummary, MenuSettings, MenuView, daily_challenge_text,
+        draw_exit_screen_text, draw_game_over_screen_text, draw_info_text, draw_menu_screen_text,
+        draw_overlay_veil, draw_players_info_text, draw_race_results_text, draw_title_screen_text,
+        draw_win_screen_text, high_score_text, player_info_text, practice_settings_text,
+        race_results_text,
     },
 };
 use bevy_ecs::{
+    bundle::Bundle,
     entity::Entity,
-    query::With,
-    schedule::Schedule,
-    system::{Query, Res, ResMut},
-    world::World,
+    message::{Message, MessageReader, MessageWriter, message_update_system},
+    query::{Has, Or, With, Without},
+    schedule::{IntoScheduleConfigs, Schedule},
+    system::{Commands, EntityCommands, NonSendMut, Query, Res, ResMut, SystemParam},
 };
 use futures::executor::block_on;
 use macroquad::{
     audio::{PlaySoundParams, Sound, play_sound},
     input::{
-        KeyCode, MouseButton, is_key_down, is_key_released, is_mouse_button_pressed,
-        is_quit_requested,
+        KeyCode, MouseButton, get_last_key_pressed, is_key_down, is_mouse_button_pressed,
+        is_quit_requested, mouse_delta_position, mouse_position,
     },
-    logging::{self, info},
+    logging::info,
+    math::Vec2,
     shapes::draw_rectangle,
     time::{get_frame_time, get_time},
+    window::set_fullscreen,
 };
 
-pub async fn spawn_entities(world: &mut World) {
-    let _flipper_entity = world
-        .spawn((
-            Flipper {},
-            RectangleShape {
-                width: 20.0,
-                height: 20.0,
-            },
-            YINMNBLUE,
-            Position {
-                x: 20.0,
-                y: 0.5 * WINDOW_HEIGHT - 10.0,
-            },
-            Velocity { x: 240.0, y: 0.0 },
-            Score { value: 0 },
-        ))
-        .id();
-    let _finish_line_entity = world
-        .spawn((
+/// Local player `number`, flapping with the first key bound to their flap action.
+#[must_use]
+pub fn local_player(number: u8, controls: &Controls) -> Player {
+    let action = if number == 1 {
+        Action::Flap
+    } else {
+        Action::FlapPlayerTwo
+    };
+    let flap_key = controls
+        .keys(action)
+        .first()
+        .copied()
+        .unwrap_or(KeyCode::Space);
+
+    Player { number, flap_key }
+}
+
+/// Starting height, with the second player a little lower so both flippers stay visible.
+fn flipper_start_y(player: Player) -> f32 {
+    0.5 * VIRTUAL_HEIGHT - 10.0 + 40.0 * f32::from(player.number.saturating_sub(1))
+}
+
+/// Where flippers start each run, from the left of the course.
+const FLIPPER_START_X: f32 = 20.0;
+
+/// Course scale for the distance the HUD shows.
+const PIXELS_PER_METRE: f32 = 40.0;
+
+#[must_use]
+pub fn flipper_bundle(player: Player, colour: Colour) -> impl Bundle {
+    let flipper_shape = RectangleShape {
+        width: 20.0,
+        height: 20.0,
+    };
+    let flipper_hitbox = Hitbox { inset: 2.0 };
+    let flipper_collider = Collider::hitbox(&flipper_shape, &flipper_hitbox);
+    (
+        Flipper {},
+        player,
+        flipper_shape,
+        flipper_hitbox,
+        colour,
+        Position {
+            x: FLIPPER_START_X,
+            y: flipper_start_y(player),
+        },
+        PreviousPosition::default(),
+        flipper_collider,
+        Velocity {
+            x: BASE_PAN_SPEED,
+            y: 0.0,
+        },
+        Score::default(),
+        Combo::default(),
+        RespawnPoint { x: FLIPPER_START_X },
+        RunEntity,
+    )
+}
+
+/// Spawns `bundle` as part of the course, recording the horizontal extent it can ever cover in
+/// the course index.
+fn spawn_placed<'a>(
+    commands: &'a mut Commands,
+    course_index: &mut CourseIndex,
+    bundle: impl Bundle,
+    (left, right): (f32, f32),
+) -> EntityCommands<'a> {
+    let result = commands.spawn((bundle, Placed));
+    course_index.insert(result.id(), left, right);
+
+    result
+}
+
+/// Pull range of the magnet power-up.
+const MAGNET_RADIUS: f32 = 150.0;
+
+/// Furthest a pickup strays from where it was placed: drawn in by a magnet, then grown about its
+/// centre by the pickup effect.
+const PICKUP_REACH: f32 = MAGNET_RADIUS + 100.0;
+
+/// Spawns the obstacles, checkpoints, finish line and pickups laid out in `level`, with gaps
+/// sized for the difficulty, indexing each by where it lies along the course.
+fn spawn_level(
+    commands: &mut Commands,
+    course_index: &mut CourseIndex,
+    level: &Level,
+    difficulty: Difficulty,
+    theme: &Theme,
+) {
+    course_index.clear();
+    spawn_placed(
+        commands,
+        course_index,
+        (
             FinishLine,
+            RunEntity,
             RectangleShape {
                 width: 5.0,
-                height: WINDOW_HEIGHT,
+                height: VIRTUAL_HEIGHT,
             },
-            COLUMBIABLUE,
+            theme.finish_line,
+            Collider::rectangle(5.0, VIRTUAL_HEIGHT),
             Position {
-                x: 4.0 * WINDOW_WIDTH,
+                x: level.finish_line_x,
                 y: 0.0,
             },
-        ))
-        .id();
-    world.spawn((
-        Position { x: 600.0, y: 0.0 },
-        DARKPASTELGREEN,
-        ObstacleShape {
-            gap_length: 100.0,
-            gap_y_displacement: 300.0,
-            width: 15.0,
-        },
-    ));
-    world.spawn((
-        Position { x: 900.0, y: 0.0 },
-        DARKPASTELGREEN,
-        ObstacleShape {
-            gap_length: 75.0,
-            gap_y_displacement: 350.0,
-            width: 15.0,
-        },
-    ));
-    world.spawn((
-        Position { x: 1200.0, y: 0.0 },
-        DARKPASTELGREEN,
-        ObstacleShape {
-            gap_length: 75.0,
-            gap_y_displacement: 300.0,
+        ),
+        (level.finish_line_x, level.finish_line_x + 5.0),
+    );
+    for x in &level.checkpoints {
+        spawn_placed(
+            commands,
+            course_index,
+            (
+                Checkpoint,
+                RunEntity,
+                RectangleShape {
+                    width: 5.0,
+                    height: VIRTUAL_HEIGHT,
+                },
+                theme.checkpoint,
+                Collider::rectangle(5.0, VIRTUAL_HEIGHT),
+                Position { x: *x, y: 0.0 },
+            ),
+            (*x, *x + 5.0),
+        );
+    }
+    for spec in &level.obstacles {
+        let position = Position { x: spec.x, y: 0.0 };
+        let (gap_length, gap_y_displacement) = spec.scaled_gap(difficulty.gap_scale());
+        let shape = ObstacleShape {
+            gap_length,
+            gap_y_displacement,
             width: 15.0,
+        };
+        let (left, right) = shape.horizontal_extent(&position);
+        let (reach_left, reach_right) = spec
+            .motion
+            .as_ref()
+            .and_then(|paths| paths.x.as_ref())
+            .map_or((0.0, 0.0), MotionPath::range);
+        let mut obstacle = spawn_placed(
+            commands,
+            course_index,
+            (
+                RunEntity,
+                ClearedBy::default(),
+                theme.obstacle,
+                Collider::rectangle(15.0, VIRTUAL_HEIGHT),
+            ),
+            (left + reach_left, right + reach_right),
+        );
+        if let Some(paths) = &spec.motion {
+            obstacle.insert((
+                ObstacleMotion::new(paths.clone(), &position, &shape),
+                PreviousPosition(position.clone()),
+            ));
+        }
+        obstacle.insert((position, shape));
+    }
+    for spec in &level.collectibles {
+        spawn_placed(
+            commands,
+            course_index,
+            collectible_bundle(spec, theme),
+            (spec.x - PICKUP_REACH, spec.x + PICKUP_REACH),
+        );
+    }
+    for spec in &level.power_ups {
+        spawn_placed(
+            commands,
+            course_index,
+            power_up_bundle(spec, theme),
+            (spec.x - PICKUP_REACH, spec.x + PICKUP_REACH),
+        );
+    }
+}
+
+/// Menu choices shaping the next run.
+#[derive(SystemParam)]
+struct RunSettings<'w> {
+    difficulty: Res<'w, Difficulty>,
+    lives_settings: Res<'w, LivesSettings>,
+    play_mode: Res<'w, PlayMode>,
+    two_player_settings: Res<'w, TwoPlayerSettings>,
+}
+
+/// Spawns a player's flipper for a new run, with whatever components the run settings call for.
+fn spawn_flipper(
+    commands: &mut Commands,
+    player: Player,
+    colour: Colour,
+    run_settings: &RunSettings,
+    pan_speed: f32,
+) {
+    let mut flipper = commands.spawn(flipper_bundle(player, colour));
+    // the flipper keeps pace with the camera, so both scale together
+    flipper.insert(Velocity {
+        x: pan_speed,
+        y: 0.0,
+    });
+    if *run_settings.play_mode == PlayMode::TimeTrial {
+        flipper.insert((
+            TimeTrial::default(),
+            SpeedControl {
+                min_speed: 0.6 * pan_speed,
+                max_speed: 1.5 * pan_speed,
+                acceleration: BASE_PAN_SPEED,
+            },
+        ));
+    }
+    if run_settings.lives_settings.enabled {
+        flipper.insert(Lives {
+            remaining: run_settings.lives_settings.lives,
+        });
+    }
+}
+
+/// Restarts the run once one is due: despawns every run entity, then spawns the flippers and the
+/// selected campaign level, or today's course in the daily challenge, afresh.  Nothing from the
+/// last run carries over, whatever components it left behind.
+#[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+fn restart_run(
+    run_entity_query: Query<Entity, With<RunEntity>>,
+    campaign: Res<Campaign>,
+    controls: Res<Controls>,
+    daily_challenge: Res<DailyChallenge>,
+    run_settings: RunSettings,
+    theme: Res<Theme>,
+    mut camera: ResMut<Camera>,
+    mut contacts: ResMut<Contacts>,
+    mut course_index: ResMut<CourseIndex>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut level: ResMut<Level>,
+    mut race_result: ResMut<RaceResult>,
+    mut loaded: MessageWriter<LevelLoaded>,
+    mut commands: Commands,
+) {
+    if current_level.spawned {
+        return;
+    }
+    for entity in &run_entity_query {
+        commands.entity(entity).despawn();
+    }
+    *camera = Camera {
+        pan_speed: BASE_PAN_SPEED * run_settings.difficulty.speed_scale(),
+        ..Camera::default()
+    };
+    // forget overlaps involving the despawned entities
+    contacts.pairs.clear();
+    race_result.winner = None;
+
+    if *run_settings.play_mode == PlayMode::Daily {
+        level.clone_from(&daily_challenge.level);
+    } else if let Some(value) = campaign.levels.get(current_level.index) {
+        level.clone_from(value);
+    }
+    // medal times are set at the base speed, so slower difficulties get longer
+    level.medal_times = level
+        .medal_times
+        .for_speed(run_settings.difficulty.speed_scale());
+    info!("Starting run on level: {}", level.message_id);
+    spawn_flipper(
+        &mut commands,
+        local_player(1, &controls),
+        theme.flippers[0],
+        &run_settings,
+        camera.pan_speed,
+    );
+    if run_settings.two_player_settings.enabled {
+        spawn_flipper(
+            &mut commands,
+            local_player(2, &controls),
+            theme.flippers[1],
+            &run_settings,
+            camera.pan_speed,
+        );
+    }
+    spawn_level(
+        &mut commands,
+        &mut course_index,
+        &level,
+        *run_settings.difficulty,
+        &theme,
+    );
+    current_level.spawned = true;
+    loaded.write(LevelLoaded);
+}
+
+/// Written once a run has been freshly spawned.
+#[derive(Debug, Message)]
+pub struct LevelLoaded;
+
+/// Practice start, just ahead of the chosen obstacle, or the usual start for obstacle zero.
+fn practice_start_x(level: &Level, start_obstacle: usize) -> f32 {
+    start_obstacle
+        .checked_sub(1)
+        .and_then(|index| level.obstacles.get(index))
+        .map_or(20.0, |spec| (spec.x - 200.0).max(20.0))
+}
+
+/// Moves the flippers, camera and respawn points up to the chosen obstacle for a practice run.
+#[allow(clippy::needless_pass_by_value)]
+fn place_practice_start(
+    mut loaded: MessageReader<LevelLoaded>,
+    mut flipper_query: Query<
+        (&mut Position, &mut PreviousPosition, &mut RespawnPoint),
+        With<Flipper>,
+    >,
+    level: Res<Level>,
+    play_mode: Res<PlayMode>,
+    practice_settings: Res<PracticeSettings>,
+    mut camera: ResMut<Camera>,
+) {
+    if loaded.read().count() == 0 || *play_mode != PlayMode::Practice {
+        return;
+    }
+    let start_x = practice_start_x(&level, practice_settings.start_obstacle);
+    for (mut position, mut previous_position, mut respawn_point) in &mut flipper_query {
+        position.x = start_x;
+        previous_position.0 = position.clone();
+        respawn_point.x = start_x;
+    }
+    camera.left_displacement = start_x - 20.0;
+}
+
+/// Saved records a finished single-player run counts towards: campaign progress, or today's
+/// daily challenge.
+#[derive(SystemParam)]
+struct RunRecords<'w> {
+    campaign: Res<'w, Campaign>,
+    difficulty: Res<'w, Difficulty>,
+    play_mode: Res<'w, PlayMode>,
+    two_player_settings: Res<'w, TwoPlayerSettings>,
+    current_level: ResMut<'w, CurrentLevel>,
+    daily_challenge: ResMut<'w, DailyChallenge>,
+    progress: ResMut<'w, Progress>,
+}
+
+impl RunRecords<'_> {
+    /// Stores the result of a finished run.  Completing a campaign level also lines up the
+    /// next level, now unlocked, on the level select screen.
+    fn record(&mut self, score: &Score, completed: bool) {
+        if *self.play_mode == PlayMode::Practice {
+            info!("Practice run finished, score not recorded");
+            return;
+        }
+        // a race is scored against the other player, not against solo bests
+        if self.two_player_settings.enabled {
+            info!("Race finished, scores not recorded");
+            return;
+        }
+        if *self.play_mode == PlayMode::Daily {
+            if self.daily_challenge.record(*self.difficulty, score.value) {
+                info!("New best daily challenge score: {}", score.value);
+            }
+            self.daily_challenge.save(&save_path(DAILY_FILE_NAME));
+            return;
+        }
+
+        let index = self.current_level.index;
+        if self
+            .progress
+            .record(index, *self.difficulty, score.value, completed)
+        {
+            info!("New best score for level: {}", score.value);
+        }
+        self.progress.save(&save_path(PROGRESS_FILE_NAME));
+        if completed && index + 1 < self.campaign.levels.len() {
+            self.current_level.index += 1;
+        }
+    }
+}
+
+/// Saved best score the current run is trying to beat, read-only for the HUD.
+#[derive(SystemParam)]
+struct PersonalBest<'w> {
+    difficulty: Res<'w, Difficulty>,
+    play_mode: Res<'w, PlayMode>,
+    current_level: Res<'w, CurrentLevel>,
+    daily_challenge: Res<'w, DailyChallenge>,
+    progress: Res<'w, Progress>,
+}
+
+impl PersonalBest<'_> {
+    /// `None` for practice runs, which are not recorded, and before any run has been.
+    fn score(&self) -> Option<u32> {
+        match *self.play_mode {
+            PlayMode::Practice => None,
+            PlayMode::Daily => self.daily_challenge.best_score(*self.difficulty),
+            _ => self
+                .progress
+                .levels
+                .get(self.current_level.index)
+                .and_then(|value| value.best_score(*self.difficulty)),
+        }
+    }
+}
+
+fn collectible_bundle(spec: &CollectibleSpec, theme: &Theme) -> impl Bundle {
+    let CollectibleSpec { kind, x, y, value } = *spec;
+    let (size, colour) = match kind {
+        CollectibleKind::Pearl => (10.0, theme.pearl),
+        CollectibleKind::Shell => (14.0, theme.shell),
+    };
+
+    (
+        Collectible { kind, value },
+        RectangleShape {
+            width: size,
+            height: size,
         },
-    ));
-    world.spawn((
-        Position { x: 1500.0, y: 0.0 },
-        DARKPASTELGREEN,
-        ObstacleShape {
-            gap_length: 75.0,
-            gap_y_displacement: 300.0,
-            width: 15.0,
+        colour,
+        Position { x, y },
+        Collider::rectangle(size, size),
+        RunEntity,
+    )
+}
+
+fn power_up_bundle(spec: &PowerUpSpec, theme: &Theme) -> impl Bundle {
+    let PowerUpSpec { kind, x, y } = *spec;
+    let colour = match kind {
+        PowerUpKind::Magnet => theme.magnet,
+        PowerUpKind::Shield => theme.shield,
+        PowerUpKind::SlowMotion => theme.slow_motion,
+    };
+
+    (
+        PowerUp { kind },
+        RectangleShape {
+            width: 16.0,
+            height: 16.0,
         },
-    ));
+        colour,
+        Position { x, y },
+        Collider::rectangle(16.0, 16.0),
+        RunEntity,
+    )
 }
 
 pub fn initialise_fonts(mut game_assets: ResMut<GameAssets>) {
-    let game_fonts = &mut game_assets.fonts;
-    block_on(AssetManager::load_fonts(game_fonts));
+    block_on(AssetManager::load_fonts(&mut game_assets.fonts));
 }
 
 pub fn initialise_sound_resources(game_state: ResMut<GameAssets>) {
@@ -115,16 +508,49 @@ pub fn initialise_sound_resources(game_state: ResMut<GameAssets>) {
     block_on(AssetManager::load_sounds(game_sounds));
 }
 
-pub fn update_delta_time(mut delta_time: ResMut<DeltaTime>) {
-    delta_time.seconds = get_frame_time();
+#[allow(clippy::needless_pass_by_value)]
+pub fn update_delta_time(
+    query: Query<&SlowMotion, With<Flipper>>,
+    play_mode: Res<PlayMode>,
+    practice_settings: Res<PracticeSettings>,
+    mut delta_time: ResMut<DeltaTime>,
+) {
+    let unscaled_seconds = get_frame_time();
+    let global_time_scale = if *play_mode == PlayMode::Practice {
+        practice_settings.time_scale()
+    } else {
+        1.0
+    };
+    let time_scale = query
+        .iter()
+        .map(|slow_motion| slow_motion.time_scale)
+        .fold(global_time_scale, f32::min);
+    delta_time.unscaled_seconds = unscaled_seconds;
+    delta_time.seconds = time_scale * unscaled_seconds;
 }
 
-#[allow(clippy::needless_pass_by_value)]
-fn draw_rectangles(query: Query<(&Position, &RectangleShape, &Colour)>, camera: Res<Camera>) {
-    for (position, shape, colour) in query.iter() {
-        let Position { x, y } = position;
-        let RectangleShape { width, height } = shape;
-        if camera.in_view(*x) {
+/// Draws the flippers and whichever course rectangles the index finds near the camera.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn draw_rectangles(
+    query: Query<(
+        &Position,
+        &RectangleShape,
+        &Colour,
+        Option<&Invulnerable>,
+        Has<Eliminated>,
+    )>,
+    unplaced_query: Query<Entity, (With<RectangleShape>, Without<Placed>)>,
+    camera: Res<Camera>,
+    course_index: Res<CourseIndex>,
+) {
+    let nearby = unplaced_query.iter().chain(course_index.near_view(&camera));
+    for (position, shape, colour, invulnerable, eliminated) in query.iter_many(nearby) {
+        if eliminated || invulnerable.is_some_and(Invulnerable::blink_hidden) {
+            continue;
+        }
+        if camera.in_view(shape, position) {
+            let Position { x, y } = position;
+            let RectangleShape { width, height } = shape;
             draw_rectangle(
                 *x - camera.left_displacement,
                 *y,
@@ -136,11 +562,74 @@ fn draw_rectangles(query: Query<(&Position, &RectangleShape, &Colour)>, camera:
     }
 }
 
+/// Flipper positions over the next `steps` frames if no flap is made, mirroring the per-frame
+/// gravity in `update_flipper_vertical_position`.
+fn predict_trajectory(
+    position: &Position,
+    velocity: &Velocity,
+    gravity: f32,
+    frame_seconds: f32,
+    steps: usize,
+) -> Vec<Position> {
+    let mut result = Vec::with_capacity(steps);
+    let mut predicted = position.clone();
+    let mut velocity_y = velocity.y;
+    for _ in 0..steps {
+        if velocity_y < TERMINAL_SINK_SPEED {
+            velocity_y += frame_seconds * gravity;
+        }
+        predicted.x += frame_seconds * velocity.x;
+        predicted.y += frame_seconds * velocity_y;
+        result.push(predicted.clone());
+    }
+
+    result
+}
+
+const TRAJECTORY_FRAMES: usize = 45;
+
+/// Dots out each flipper's predicted path during practice runs.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn draw_predicted_trajectory(
+    query: Query<(&Position, &Velocity, &RectangleShape), (With<Flipper>, Without<Eliminated>)>,
+    camera: Res<Camera>,
+    delta_time: Res<DeltaTime>,
+    difficulty: Res<Difficulty>,
+    play_mode: Res<PlayMode>,
+    theme: Res<Theme>,
+) {
+    if *play_mode != PlayMode::Practice {
+        return;
+    }
+    for (position, velocity, shape) in &query {
+        let trajectory = predict_trajectory(
+            position,
+            velocity,
+            difficulty.gravity(),
+            delta_time.seconds,
+            TRAJECTORY_FRAMES,
+        );
+        for Position { x, y } in trajectory.iter().step_by(3) {
+            draw_rectangle(
+                x + 0.5 * shape.width - 2.0 - camera.left_displacement,
+                y + 0.5 * shape.height - 2.0,
+                4.0,
+                4.0,
+                theme.trajectory.into(),
+            );
+        }
+    }
+}
+
 #[allow(clippy::needless_pass_by_value)]
-fn draw_obstacles(query: Query<(&Position, &ObstacleShape, &Colour)>, camera: Res<Camera>) {
-    for (position, shape, colour) in query.iter() {
-        let Position { x, .. } = position;
-        if camera.in_view(*x) {
+fn draw_obstacles(
+    query: Query<(&Position, &ObstacleShape, &Colour)>,
+    camera: Res<Camera>,
+    course_index: Res<CourseIndex>,
+) {
+    for (position, shape, colour) in query.iter_many(course_index.near_view(&camera)) {
+        if camera.in_view(shape, position) {
+            let Position { x, .. } = position;
             if shape.gap_y_displacement > 0.0 {
                 draw_rectangle(
                     *x - camera.left_displacement,
@@ -151,12 +640,12 @@ fn draw_obstacles(query: Query<(&Position, &ObstacleShape, &Colour)>, camera: Re
                 );
             }
             let bottom_section_top = shape.gap_bottom();
-            if bottom_section_top < WINDOW_HEIGHT {
+            if bottom_section_top < VIRTUAL_HEIGHT {
                 draw_rectangle(
                     *x - camera.left_displacement,
                     bottom_section_top,
                     shape.width,
-                    WINDOW_HEIGHT - bottom_section_top,
+                    VIRTUAL_HEIGHT - bottom_section_top,
                     (*colour).into(),
                 );
             }
@@ -164,126 +653,387 @@ fn draw_obstacles(query: Query<(&Position, &ObstacleShape, &Colour)>, camera: Re
     }
 }
 
-fn handle_before_transition_to_won(
-    background_sound: Option<&Sound>,
-    victory_sound: Option<&Sound>,
+/// The first flipper across the finish line wins the run.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn handle_flipper_finish_line_collision(
+    mut collisions: MessageReader<CollisionStarted>,
+    flipper_query: Query<(Entity, &Player, &Score), (With<Flipper>, Without<Eliminated>)>,
+    finish_line_query: Query<(), With<FinishLine>>,
+    mut game_state: ResMut<GameState>,
+    mut race_result: ResMut<RaceResult>,
+    mut run_records: RunRecords,
 ) {
-    if let Some(value) = background_sound {
-        stop_playing_looped_sound(value);
+    for CollisionStarted { pair, .. } in collisions.read() {
+        let Some((_, player, score)) = flipper_query.iter().find(|(flipper_entity, ..)| {
+            pair.other(*flipper_entity)
+                .is_some_and(|entity| finish_line_query.contains(entity))
+        }) else {
+            continue;
+        };
+        info!("Player {} crossed the finish line", player.number);
+        race_result.winner = Some(player.number);
+        run_records.record(score, true);
+        game_state.queue(GameMode::Won);
+        break;
     }
-    if let Some(value) = victory_sound {
-        play_sound_once(value);
+}
+
+/// Moves each flipper's respawn point up to the checkpoints it passes.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn handle_flipper_checkpoint_collision(
+    mut collisions: MessageReader<CollisionStarted>,
+    mut flipper_query: Query<(Entity, &mut RespawnPoint), (With<Flipper>, Without<Eliminated>)>,
+    checkpoint_query: Query<&Position, With<Checkpoint>>,
+) {
+    for CollisionStarted { pair, .. } in collisions.read() {
+        for (flipper_entity, mut respawn_point) in &mut flipper_query {
+            if let Some(position) = pair
+                .other(flipper_entity)
+                .and_then(|entity| checkpoint_query.get(entity).ok())
+                && position.x > respawn_point.x
+            {
+                info!("Checkpoint passed");
+                respawn_point.x = position.x;
+            }
+        }
     }
 }
 
-#[allow(clippy::needless_pass_by_value)]
-fn handle_flipper_finish_line_collision(
-    flipper_query: Query<(&Position, &RectangleShape), With<Flipper>>,
-    finish_line_query: Query<(&FinishLine, &Position, &RectangleShape)>,
-    game_assets: Res<GameAssets>,
+const INVULNERABLE_SECONDS: f32 = 2.0;
+
+/// How far ahead of the camera's left edge a respawning flipper reappears.
+const RESPAWN_CAMERA_LEAD: f32 = 20.0;
+
+/// Resolves this frame's hits: with a life to spare a flipper goes back to its respawn point
+/// and blinks for a while, otherwise it is out.  Obstacles ahead of the respawn point count as
+/// uncleared again, so the flipper has to clear them afresh.  Several hits on one flipper in one
+/// frame cost one life.  The run is over once every flipper is out.
+#[allow(
+    clippy::needless_pass_by_value,
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn handle_flipper_hit(
+    mut hits: MessageReader<FlipperHit>,
+    mut flipper_query: Query<
+        (
+            &Player,
+            &mut Position,
+            &mut PreviousPosition,
+            &mut Velocity,
+            &RespawnPoint,
+            &Score,
+            &mut Combo,
+            Option<&mut Lives>,
+            Has<Eliminated>,
+        ),
+        With<Flipper>,
+    >,
+    mut obstacle_query: Query<(&Position, &mut ClearedBy), Without<Flipper>>,
+    mut camera: ResMut<Camera>,
     mut game_state: ResMut<GameState>,
-) -> bevy_ecs::error::Result<()> {
-    let (flipper_position, flipper_shape) = flipper_query.single()?;
-    for (_finish_line, finish_line_position, finish_line_shape) in finish_line_query.iter() {
-        if flipper_shape.right(flipper_position) > finish_line_shape.left(finish_line_position)
-            && flipper_shape.left(flipper_position) < finish_line_shape.right(finish_line_position)
-        {
-            game_state.mode = GameMode::Won;
-            handle_before_transition_to_won(
-                game_assets.sounds.background.as_ref(),
-                game_assets.sounds.victory.as_ref(),
-            );
+    mut race_result: ResMut<RaceResult>,
+    mut run_records: RunRecords,
+    mut commands: Commands,
+) {
+    let mut hit_flippers: Vec<(Entity, &FlipperHitCause)> = Vec::new();
+    for FlipperHit { flipper, cause } in hits.read() {
+        if !hit_flippers.iter().any(|(entity, _)| entity == flipper) {
+            hit_flippers.push((*flipper, cause));
         }
     }
+    if hit_flippers.is_empty() {
+        return;
+    }
 
-    Ok(())
-}
+    let player_count = flipper_query.iter().count();
+    let mut eliminated_now: Vec<(u8, u32)> = Vec::new();
+    for (flipper_entity, cause) in hit_flippers {
+        let Ok((
+            player,
+            mut position,
+            mut previous_position,
+            mut velocity,
+            respawn_point,
+            score,
+            mut combo,
+            lives,
+            eliminated,
+        )) = flipper_query.get_mut(flipper_entity)
+        else {
+            continue;
+        };
+        if eliminated {
+            continue;
+        }
+        combo.streak = 0;
 
-fn handle_exit(mut game_state: ResMut<'_, GameState>) {
-    if is_key_released(KeyCode::Escape) {
-        if let GameMode::Exiting(value) = &game_state.mode {
-            match value {
-                ResumeGameMode::Playing => game_state.mode = GameMode::Playing,
-                ResumeGameMode::Menu => game_state.mode = GameMode::Menu,
+        if lives.is_some_and(|mut value| value.lose_life()) {
+            info!(
+                "Player {} lost a life ({cause:?}), respawning at checkpoint",
+                player.number
+            );
+            // in a race the camera carries on for the other player, so respawn within view
+            position.x = if player_count > 1 {
+                respawn_point
+                    .x
+                    .max(camera.left_displacement + RESPAWN_CAMERA_LEAD)
+            } else {
+                camera.left_displacement = respawn_point.x - RESPAWN_CAMERA_LEAD;
+                respawn_point.x
+            };
+            position.y = flipper_start_y(*player);
+            previous_position.0 = position.clone();
+            velocity.y = 0.0;
+            commands.entity(flipper_entity).insert(Invulnerable {
+                remaining: INVULNERABLE_SECONDS,
+            });
+            for (obstacle_position, mut cleared_by) in &mut obstacle_query {
+                if obstacle_position.x > position.x {
+                    cleared_by.remove(*player);
+                }
             }
         } else {
-            logging::error!("Handle exit called from outside GameMode::Exiting");
-            game_state.mode = GameMode::Menu;
+            info!("Player {} is out: {cause:?}", player.number);
+            commands.entity(flipper_entity).insert(Eliminated);
+            eliminated_now.push((player.number, score.value));
         }
     }
+
+    let still_racing = flipper_query
+        .iter()
+        .filter(|(.., eliminated)| !eliminated)
+        .count()
+        - eliminated_now.len();
+    if eliminated_now.is_empty() || still_racing > 0 {
+        return;
+    }
+    info!("Game Over triggered");
+    race_result.winner = last_player_out(&eliminated_now);
+    if let Some(score) = flipper_query
+        .iter()
+        .map(|(_, _, _, _, _, score, ..)| score)
+        .max_by_key(|score| score.value)
+    {
+        run_records.record(score, false);
+    }
+    game_state.queue(GameMode::GameOver);
 }
 
-fn handle_before_transition_to_game_over(
-    background_sound: Option<&Sound>,
-    game_over_sound: Option<&Sound>,
+/// Despawns the course entities the run has left behind for good: wholly left of the camera's
+/// view bounds, and of the view a single flipper respawning would take the camera back to, so a
+/// respawn never returns to a course with gaps in it.
+#[allow(clippy::needless_pass_by_value)]
+fn despawn_passed_entities(
+    flipper_query: Query<&RespawnPoint, (With<Flipper>, Without<Eliminated>)>,
+    camera: Res<Camera>,
+    mut course_index: ResMut<CourseIndex>,
+    mut commands: Commands,
 ) {
-    if let Some(value) = background_sound {
-        stop_playing_looped_sound(value);
+    let (view_left, _) = camera.view_bounds();
+    let passed_x = flipper_query
+        .iter()
+        .map(|respawn_point| respawn_point.x - RESPAWN_CAMERA_LEAD - camera.view_margin)
+        .fold(view_left, f32::min);
+    for entity in course_index.drain_behind(passed_x) {
+        // picked-up collectibles may already have gone
+        commands.entity(entity).try_despawn();
     }
-    if let Some(value) = game_over_sound {
-        play_sound_once(value);
+}
+
+/// Winner of a race where everyone is out, from the players, with their scores, who went out
+/// on the final frame: the last player out wins, and a tie goes to the higher score.
+fn last_player_out(eliminated_last: &[(u8, u32)]) -> Option<u8> {
+    let best_score = eliminated_last.iter().map(|(_, score)| *score).max()?;
+    let mut leaders = eliminated_last
+        .iter()
+        .filter(|(_, score)| *score == best_score);
+    match (leaders.next(), leaders.next()) {
+        (Some((number, _)), None) => Some(*number),
+        _ => None,
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_exit(controls: Res<Controls>, mut game_state: ResMut<'_, GameState>) {
+    if controls.is_released(Action::Back) {
+        let next = match game_state.mode() {
+            GameMode::Exiting(beneath) => (**beneath).clone(),
+            _ => GameMode::Menu,
+        };
+        game_state.queue(next);
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum FlipperHitCause {
+    Floor,
+    Obstacle,
+}
+
+/// Written when a flipper hits something which costs a life, or puts it out of the run once
+/// none are left.
+#[derive(Debug, Message)]
+pub struct FlipperHit {
+    pub flipper: Entity,
+    pub cause: FlipperHitCause,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum FlipperObstacleCollision {
     Gap,
     Obstacle,
 }
 
+/// Horizontal contact needs the flipper hitbox and obstacle to share some interior, so touching
+/// side edges is not a collision.  The gap is inclusive: a hitbox exactly touching the gap top or
+/// bottom edge still clears it.
 fn obstacle_flipper_collision(
     obstacle_shape: &ObstacleShape,
     obstacle_position: &Position,
     flipper_shape: &RectangleShape,
+    flipper_hitbox: &Hitbox,
     flipper_position: &Position,
 ) -> Option<FlipperObstacleCollision> {
-    let horizontal_overlap = flipper_shape.right(flipper_position)
-        > obstacle_shape.left(obstacle_position)
-        && flipper_shape.left(flipper_position) < obstacle_shape.right(obstacle_position);
+    let Hitbox { inset } = *flipper_hitbox;
+    let hitbox_left = flipper_shape.left(flipper_position) + inset;
+    let hitbox_right = flipper_shape.right(flipper_position) - inset;
+
+    let horizontal_overlap = hitbox_right > obstacle_shape.left(obstacle_position)
+        && hitbox_left < obstacle_shape.right(obstacle_position);
 
     if !horizontal_overlap {
         return None;
     }
 
-    if flipper_shape.bottom(flipper_position) < obstacle_shape.gap_bottom()
-        && flipper_shape.top(flipper_position) > obstacle_shape.gap_top()
-    {
-        Some(FlipperObstacleCollision::Gap)
+    Some(gap_judgement(
+        obstacle_shape,
+        flipper_shape,
+        flipper_hitbox,
+        flipper_position,
+    ))
+}
+
+/// Whether the flipper hitbox sits within the obstacle gap's height, ignoring where it is
+/// across the obstacle.
+fn gap_judgement(
+    obstacle_shape: &ObstacleShape,
+    flipper_shape: &RectangleShape,
+    flipper_hitbox: &Hitbox,
+    flipper_position: &Position,
+) -> FlipperObstacleCollision {
+    let Hitbox { inset } = *flipper_hitbox;
+    let hitbox_top = flipper_shape.top(flipper_position) + inset;
+    let hitbox_bottom = flipper_shape.bottom(flipper_position) - inset;
+
+    if hitbox_top >= obstacle_shape.gap_top() && hitbox_bottom <= obstacle_shape.gap_bottom() {
+        FlipperObstacleCollision::Gap
     } else {
-        Some(FlipperObstacleCollision::Obstacle)
+        FlipperObstacleCollision::Obstacle
     }
 }
 
-#[allow(clippy::needless_pass_by_value)]
-fn handle_obstacle_flipper_collision(
-    obstacle_query: Query<'_, '_, (Entity, &Position, &ObstacleShape)>,
-    mut flipper_query: Query<(&mut Score, &Position, &RectangleShape), With<Flipper>>,
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+pub fn handle_obstacle_flipper_collision(
+    mut collisions: MessageReader<CollisionStarted>,
+    mut obstacle_query: Query<(&Position, &ObstacleShape, &mut ClearedBy)>,
+    mut flipper_query: Query<
+        (
+            Entity,
+            &Player,
+            &mut Score,
+            &mut Combo,
+            &Position,
+            &PreviousPosition,
+            &RectangleShape,
+            &Hitbox,
+            Has<Shield>,
+            Has<Invulnerable>,
+            Option<&mut TimeTrial>,
+        ),
+        (With<Flipper>, Without<Eliminated>),
+    >,
     game_assets: Res<GameAssets>,
-    mut game_state: ResMut<GameState>,
-    mut cleared_obstacles: ResMut<ClearedObstacles>,
-) -> bevy_ecs::error::Result<()> {
-    let (mut score, flipper_position, flipper_shape) = flipper_query.single_mut()?;
-    for (entity, obstacle_position, obstacle_shape) in obstacle_query.iter() {
-        if !cleared_obstacles.obstacles.contains(&entity) {
-            if let Some(value) = obstacle_flipper_collision(
-                obstacle_shape,
-                obstacle_position,
-                flipper_shape,
-                flipper_position,
-            ) {
+    mut hits: MessageWriter<FlipperHit>,
+    mut commands: Commands,
+) {
+    let collisions: Vec<&CollisionStarted> = collisions.read().collect();
+    for (
+        flipper_entity,
+        player,
+        mut score,
+        mut combo,
+        flipper_position,
+        flipper_previous_position,
+        flipper_shape,
+        flipper_hitbox,
+        mut shielded,
+        invulnerable,
+        mut time_trial,
+    ) in &mut flipper_query
+    {
+        for CollisionStarted { pair, time } in collisions.iter().copied() {
+            let Some(entity) = pair.other(flipper_entity) else {
+                continue;
+            };
+            let Ok((obstacle_position, obstacle_shape, mut cleared_by)) =
+                obstacle_query.get_mut(entity)
+            else {
+                continue;
+            };
+            if !cleared_by.contains(*player) {
+                // judge the gap where the flipper entered the obstacle, which may be well before
+                // the end of a long frame, and again where the frame leaves it if still crossing
+                let PreviousPosition(previous) = flipper_previous_position;
+                let entry_position = flipper_position.interpolate_from(previous, *time);
+                let at_entry = gap_judgement(
+                    obstacle_shape,
+                    flipper_shape,
+                    flipper_hitbox,
+                    &entry_position,
+                );
+                let at_end = obstacle_flipper_collision(
+                    obstacle_shape,
+                    obstacle_position,
+                    flipper_shape,
+                    flipper_hitbox,
+                    flipper_position,
+                );
+                let value = at_end
+                    .filter(|value| *value == FlipperObstacleCollision::Obstacle)
+                    .unwrap_or(at_entry);
                 match value {
+                    FlipperObstacleCollision::Obstacle if shielded => {
+                        info!("Shield absorbed Flipper-Obstacle collision");
+                        shielded = false;
+                        commands.entity(flipper_entity).remove::<Shield>();
+                        cleared_by.insert(*player);
+                        if let Some(value) = time_trial.as_deref_mut() {
+                            value.splits.push(value.elapsed);
+                        }
+                        if let Some(value) = &game_assets.sounds.pickup {
+                            play_sound_once(value);
+                        }
+                    }
+                    FlipperObstacleCollision::Obstacle if invulnerable => {
+                        info!("Flipper-Obstacle collision ignored while invulnerable");
+                    }
                     FlipperObstacleCollision::Obstacle => {
-                        info!("Game Over triggered: Flipper-Obstacle collision");
-                        handle_before_transition_to_game_over(
-                            game_assets.sounds.background.as_ref(),
-                            game_assets.sounds.game_over.as_ref(),
-                        );
-                        game_state.mode = GameMode::GameOver;
+                        info!("Flipper-Obstacle collision");
+                        hits.write(FlipperHit {
+                            flipper: flipper_entity,
+                            cause: FlipperHitCause::Obstacle,
+                        });
                     }
                     FlipperObstacleCollision::Gap => {
                         info!("Obstacle cleared");
-                        score.value += 1;
-                        cleared_obstacles.obstacles.push(entity);
+                        combo.streak += 1;
+                        score.value += combo.multiplier();
+                        score.obstacles_cleared += 1;
+                        cleared_by.insert(*player);
+                        if let Some(value) = time_trial.as_deref_mut() {
+                            value.splits.push(value.elapsed);
+                        }
                         if let Some(value) = &game_assets.sounds.obstacle_cleared {
                             play_sound_once(value);
                         }
@@ -292,232 +1042,1009 @@ fn handle_obstacle_flipper_collision(
             }
         }
     }
-
-    Ok(())
 }
 
-#[allow(clippy::needless_pass_by_value)]
-pub fn update_camera(delta_time: Res<DeltaTime>, mut camera: ResMut<Camera>) {
-    camera.update(delta_time.seconds);
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn handle_flipper_collectible_collision(
+    mut collisions: MessageReader<CollisionStarted>,
+    collectible_query: Query<&Collectible>,
+    mut flipper_query: Query<(Entity, &mut Score), (With<Flipper>, Without<Eliminated>)>,
+    game_assets: Res<GameAssets>,
+    mut commands: Commands,
+) {
+    // the collectible lingers until commands apply, so both players reaching it in one frame
+    // must not both score it
+    let mut collected: Vec<Entity> = Vec::new();
+    for CollisionStarted { pair, .. } in collisions.read() {
+        for (flipper_entity, mut score) in &mut flipper_query {
+            let Some(entity) = pair.other(flipper_entity) else {
+                continue;
+            };
+            let Ok(Collectible { kind, value }) = collectible_query.get(entity) else {
+                continue;
+            };
+            if collected.contains(&entity) {
+                continue;
+            }
+            collected.push(entity);
+            info!("Collected {kind:?}");
+            score.value += value;
+            score.items_collected += 1;
+            if let Some(value) = &game_assets.sounds.pickup {
+                play_sound_once(value);
+            }
+            commands
+                .entity(entity)
+                .remove::<(Collectible, Collider)>()
+                .insert(PickupEffect::default());
+        }
+    }
 }
 
-#[allow(clippy::needless_pass_by_value)]
-pub fn update_positions(mut query: Query<(&mut Position, &Velocity)>, delta_time: Res<DeltaTime>) {
-    for (mut position, velocity) in &mut query {
-        position.x += delta_time.seconds * velocity.x;
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn handle_flipper_power_up_collision(
+    mut collisions: MessageReader<CollisionStarted>,
+    power_up_query: Query<&PowerUp>,
+    flipper_query: Query<Entity, (With<Flipper>, Without<Eliminated>)>,
+    game_assets: Res<GameAssets>,
+    mut commands: Commands,
+) {
+    let mut collected: Vec<Entity> = Vec::new();
+    for CollisionStarted { pair, .. } in collisions.read() {
+        for flipper_entity in &flipper_query {
+            let Some(entity) = pair.other(flipper_entity) else {
+                continue;
+            };
+            let Ok(PowerUp { kind }) = power_up_query.get(entity) else {
+                continue;
+            };
+            if collected.contains(&entity) {
+                continue;
+            }
+            collected.push(entity);
+            info!("Power-up {kind:?} collected");
+            let remaining = kind.duration();
+            // collecting a power-up already active restarts its timer
+            let mut flipper = commands.entity(flipper_entity);
+            match kind {
+                PowerUpKind::Magnet => flipper.insert(Magnet {
+                    remaining,
+                    radius: MAGNET_RADIUS,
+                    speed: 360.0,
+                }),
+                PowerUpKind::Shield => flipper.insert(Shield { remaining }),
+                PowerUpKind::SlowMotion => flipper.insert(SlowMotion {
+                    remaining,
+                    time_scale: 0.5,
+                }),
+            };
+            if let Some(value) = &game_assets.sounds.pickup {
+                play_sound_once(value);
+            }
+            commands
+                .entity(entity)
+                .remove::<(PowerUp, Collider)>()
+                .insert(PickupEffect::default());
+        }
     }
 }
 
+/// Counts down a power-up effect in real time, removing it once it runs out.
 #[allow(clippy::needless_pass_by_value)]
-pub fn update_flipper_vertical_position(
-    mut query: Query<(&mut Position, &mut Velocity), With<Flipper>>,
+fn update_timed_effect<T: TimedEffect>(
+    mut query: Query<(Entity, &mut T)>,
     delta_time: Res<DeltaTime>,
-    mut game_state: ResMut<GameState>,
-) -> bevy_ecs::error::Result<()> {
-    let (mut position, mut velocity) = query.single_mut()?;
-    if velocity.y < 30.0 {
-        velocity.y += 6.0;
-    }
-
-    if position.y <= 0.0 {
-        position.y = 0.0;
-    } else if position.y > WINDOW_HEIGHT {
-        println!("Game over!");
-        game_state.mode = GameMode::GameOver;
+    mut commands: Commands,
+) {
+    for (entity, mut effect) in &mut query {
+        let remaining = effect.remaining_mut();
+        *remaining -= delta_time.unscaled_seconds;
+        if *remaining <= 0.0 {
+            commands.entity(entity).remove::<T>();
+        }
     }
-    position.y += delta_time.seconds * velocity.y;
-
-    Ok(())
 }
 
-#[allow(clippy::needless_pass_by_value)]
-pub fn handle_flipper_controls(
-    mut query: Query<&mut Velocity, With<Flipper>>,
-    game_assets: Res<GameAssets>,
-) -> bevy_ecs::error::Result<()> {
-    let mut velocity = query.single_mut()?;
-    if is_key_down(KeyCode::Space) {
-        if let Some(value) = &game_assets.sounds.flap {
-            play_sound(
-                value,
-                PlaySoundParams {
-                    looped: false,
-                    volume: 0.05,
-                },
-            );
-        }
-        if velocity.y > -180.0 {
-            velocity.y += -30.0;
+/// Draws collectibles in range towards the flipper centre.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn apply_magnet(
+    flipper_query: Query<(&Position, &RectangleShape, &Magnet), With<Flipper>>,
+    mut collectible_query: Query<
+        (&mut Position, &RectangleShape),
+        (With<Collectible>, Without<Flipper>),
+    >,
+    delta_time: Res<DeltaTime>,
+) {
+    for (flipper_position, flipper_shape, magnet) in &flipper_query {
+        let flipper_centre_x = flipper_position.x + 0.5 * flipper_shape.width;
+        let flipper_centre_y = flipper_position.y + 0.5 * flipper_shape.height;
+        for (mut position, shape) in &mut collectible_query {
+            let x_distance = flipper_centre_x - (position.x + 0.5 * shape.width);
+            let y_distance = flipper_centre_y - (position.y + 0.5 * shape.height);
+            let distance = x_distance.hypot(y_distance);
+            if distance > magnet.radius || distance < f32::EPSILON {
+                continue;
+            }
+            let step = (magnet.speed * delta_time.seconds).min(distance);
+            position.x += step * x_distance / distance;
+            position.y += step * y_distance / distance;
         }
     }
-
-    Ok(())
 }
 
-pub fn handle_replay(
-    mut query: Query<(&mut Score, &mut Position), With<Flipper>>,
-    mut camera: ResMut<Camera>,
-    mut cleared_obstacles: ResMut<ClearedObstacles>,
-    mut game_state: ResMut<GameState>,
-) -> bevy_ecs::error::Result<()> {
-    if is_key_released(KeyCode::Space) {
-        //reset camera position
-        camera.left_displacement = 0.0;
-
-        // reset flipper position and score
-        let (mut score, mut position) = query.single_mut()?;
-        score.value = 0;
-        position.x = 20.0;
-        position.y = 0.5 * WINDOW_HEIGHT - 10.0;
-
-        // reset cleared obstales
-        cleared_obstacles.obstacles = Vec::new();
-
-        // update game mode
-        game_state.mode = GameMode::Menu;
+/// Strips any active power-ups from the flipper, for example once the run is over.
+#[allow(clippy::type_complexity)]
+fn remove_power_ups(
+    query: Query<
+        Entity,
+        (
+            With<Flipper>,
+            Or<(With<Magnet>, With<Shield>, With<SlowMotion>)>,
+        ),
+    >,
+    mut commands: Commands,
+) {
+    for entity in &query {
+        commands
+            .entity(entity)
+            .remove::<(Magnet, Shield, SlowMotion)>();
     }
-
-    Ok(())
 }
 
-pub fn handle_skip_title(mut game_state: ResMut<GameState>) {
-    if is_key_released(KeyCode::Space)
-        || is_mouse_button_pressed(MouseButton::Left)
-        || get_time() > 5.0
-    {
-        game_state.mode = GameMode::Menu;
-    }
-}
-pub fn handle_request_quit(mut game_state: ResMut<GameState>) {
-    if is_key_released(KeyCode::Escape) || is_quit_requested() {
-        game_state.mode = match &game_state.mode {
-            GameMode::Playing => GameMode::Exiting(ResumeGameMode::Playing),
-            GameMode::GameOver | GameMode::Menu | GameMode::Title | GameMode::Won => {
-                GameMode::Exiting(ResumeGameMode::Menu)
-            }
-            GameMode::Exiting(_) => {
-                logging::error!("Unexpected call to handle request quit from exiting state.");
-                GameMode::Menu
-            }
-        }
-    }
-}
+const PICKUP_EFFECT_SECONDS: f32 = 0.3;
 
+/// Grows and fades picked-up collectibles about their centre, then despawns them.
 #[allow(clippy::needless_pass_by_value)]
-pub fn handle_start_game(game_assets: Res<GameAssets>, mut game_state: ResMut<GameState>) {
-    if is_key_down(KeyCode::Space) {
-        if let Some(value) = &game_assets.sounds.background {
-            start_playing_looped_sound(value);
+fn update_pickup_effects(
+    mut query: Query<(
+        Entity,
+        &mut PickupEffect,
+        &mut RectangleShape,
+        &mut Position,
+        &mut Colour,
+    )>,
+    delta_time: Res<DeltaTime>,
+    mut commands: Commands,
+) {
+    for (entity, mut effect, mut shape, mut position, mut colour) in &mut query {
+        effect.elapsed += delta_time.seconds;
+        if effect.elapsed >= PICKUP_EFFECT_SECONDS {
+            commands.entity(entity).despawn();
+            continue;
         }
-        game_state.mode = GameMode::Playing;
+
+        let growth = 1.0 + 2.0 * delta_time.seconds / PICKUP_EFFECT_SECONDS;
+        let width = shape.width * growth;
+        let height = shape.height * growth;
+        position.x -= 0.5 * (width - shape.width);
+        position.y -= 0.5 * (height - shape.height);
+        shape.width = width;
+        shape.height = height;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let alpha = (255.0 * (1.0 - effect.elapsed / PICKUP_EFFECT_SECONDS)) as u8;
+        colour.a = alpha;
     }
 }
 
+/// Boosts or brakes the flipper, with the camera keeping pace.
 #[allow(clippy::needless_pass_by_value)]
-fn update_game_over_ui(game_assets: Res<GameAssets>) {
-    let GameAssets {
-        fonts: GameFonts {
-            body: body_font, ..
-        },
-        ..
-    } = game_assets.into_inner();
-    if let Some(body_font_value) = body_font {
-        draw_game_over_screen_text(body_font_value);
+pub fn handle_speed_controls(
+    mut query: Query<(&mut Velocity, &SpeedControl), With<Flipper>>,
+    controls: Res<Controls>,
+    delta_time: Res<DeltaTime>,
+    mut camera: ResMut<Camera>,
+) {
+    for (mut velocity, speed_control) in &mut query {
+        let direction = match (
+            controls.is_down(Action::Boost),
+            controls.is_down(Action::Brake),
+        ) {
+            (true, false) => 1.0,
+            (false, true) => -1.0,
+            _ => 0.0,
+        };
+        velocity.x = speed_control.adjust(velocity.x, direction, delta_time.seconds);
+        camera.pan_speed = velocity.x;
     }
 }
 
+/// Runs the time trial clock in game time, so slow motion does not cost time.
 #[allow(clippy::needless_pass_by_value)]
-fn update_menu_ui(game_assets: Res<GameAssets>) {
-    let GameAssets {
-        fonts: GameFonts {
-            body: body_font, ..
-        },
-        ..
-    } = game_assets.into_inner();
-    if let Some(body_font_value) = body_font {
-        draw_menu_screen_text(body_font_value);
+pub fn update_time_trial(mut query: Query<&mut TimeTrial>, delta_time: Res<DeltaTime>) {
+    for mut time_trial in &mut query {
+        time_trial.elapsed += delta_time.seconds;
     }
 }
 
 #[allow(clippy::needless_pass_by_value)]
-fn update_title_ui(game_assets: Res<GameAssets>) {
-    let GameAssets {
-        fonts:
-            GameFonts {
-                body: body_font,
-                body_italic: body_italic_font,
-                heading: heading_font,
-                ..
-            },
-        ..
-    } = game_assets.into_inner();
-    if let (Some(body_font_value), Some(body_italic_font_value), Some(heading_font_value)) =
-        (body_font, body_italic_font, heading_font)
+pub fn update_camera(delta_time: Res<DeltaTime>, mut camera: ResMut<Camera>) {
+    camera.update(delta_time.seconds);
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn update_obstacle_motion(
+    mut query: Query<(&mut ObstacleMotion, &mut Position, &mut ObstacleShape)>,
+    delta_time: Res<DeltaTime>,
+) {
+    for (mut motion, mut position, mut shape) in &mut query {
+        motion.elapsed += delta_time.seconds;
+        motion.apply(&mut position, &mut shape);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn update_positions(mut query: Query<(&mut Position, &Velocity)>, delta_time: Res<DeltaTime>) {
+    for (mut position, velocity) in &mut query {
+        position.x += delta_time.seconds * velocity.x;
+    }
+}
+
+/// Fastest the flipper sinks under gravity alone.
+const TERMINAL_SINK_SPEED: f32 = 30.0;
+
+/// Fastest the flipper rises while flapping.
+const MAX_RISE_SPEED: f32 = 180.0;
+
+/// Accelerates the flipper upwards for one frame of a held flap, up to `MAX_RISE_SPEED`.
+fn flap(velocity: &mut Velocity, flap_strength: f32, frame_seconds: f32) {
+    if velocity.y > -MAX_RISE_SPEED {
+        velocity.y = (velocity.y - frame_seconds * flap_strength).max(-MAX_RISE_SPEED);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum VerticalBoundary {
+    Ceiling,
+    Floor,
+}
+
+/// The ceiling is solid: a hitbox poking up into the HUD band gets pushed back down.  The
+/// floor is open water: the flipper is only lost once its hitbox has sunk entirely below the
+/// bottom of the screen.
+fn flipper_vertical_boundary(
+    flipper_shape: &RectangleShape,
+    flipper_hitbox: &Hitbox,
+    flipper_position: &Position,
+) -> Option<VerticalBoundary> {
+    let Hitbox { inset } = *flipper_hitbox;
+    let hitbox_top = flipper_shape.top(flipper_position) + inset;
+
+    if hitbox_top < HUD_BAND_HEIGHT {
+        Some(VerticalBoundary::Ceiling)
+    } else if hitbox_top > VIRTUAL_HEIGHT {
+        Some(VerticalBoundary::Floor)
+    } else {
+        None
+    }
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+pub fn update_flipper_vertical_position(
+    mut query: Query<
+        (
+            Entity,
+            &mut Position,
+            &mut Velocity,
+            &RectangleShape,
+            &Hitbox,
+        ),
+        (With<Flipper>, Without<Eliminated>),
+    >,
+    delta_time: Res<DeltaTime>,
+    difficulty: Res<Difficulty>,
+    mut hits: MessageWriter<FlipperHit>,
+) {
+    for (flipper_entity, mut position, mut velocity, shape, hitbox) in &mut query {
+        if velocity.y < TERMINAL_SINK_SPEED {
+            velocity.y += delta_time.seconds * difficulty.gravity();
+        }
+
+        match flipper_vertical_boundary(shape, hitbox, &position) {
+            Some(VerticalBoundary::Ceiling) => position.y = HUD_BAND_HEIGHT - hitbox.inset,
+            Some(VerticalBoundary::Floor) => {
+                info!("Flipper sank below the floor");
+                hits.write(FlipperHit {
+                    flipper: flipper_entity,
+                    cause: FlipperHitCause::Floor,
+                });
+            }
+            None => {}
+        }
+        position.y += delta_time.seconds * velocity.y;
+    }
+}
+
+/// Each player flaps with their own key.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+pub fn handle_flipper_controls(
+    mut query: Query<(&mut Velocity, &Player), (With<Flipper>, Without<Eliminated>)>,
+    delta_time: Res<DeltaTime>,
+    difficulty: Res<Difficulty>,
+    game_assets: Res<GameAssets>,
+) {
+    for (mut velocity, player) in &mut query {
+        if is_key_down(player.flap_key) {
+            if let Some(value) = &game_assets.sounds.flap {
+                play_sound(
+                    value,
+                    PlaySoundParams {
+                        looped: false,
+                        volume: 0.05,
+                    },
+                );
+            }
+            flap(
+                &mut velocity,
+                difficulty.flap_strength(),
+                delta_time.seconds,
+            );
+        }
+    }
+}
+
+/// Heads back to the menu.  The next run starts from a fresh world, so there is nothing to
+/// reset here.
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_replay(controls: Res<Controls>, mut game_state: ResMut<GameState>) {
+    if controls.is_released(Action::Continue) || is_mouse_button_pressed(MouseButton::Left) {
+        game_state.queue(GameMode::Menu);
+    }
+}
+
+/// Reads gamepad events at the end of the frame, ready for the next one's systems.
+pub fn poll_gamepads(mut gamepads: NonSendMut<Gamepads>, mut controls: ResMut<Controls>) {
+    let events = gamepads.events();
+    controls.update_buttons(events);
+}
+
+#[allow(clippy::needless_pass_by_value)]
+/// Notes which device the player last used, so prompts can show its buttons.
+pub fn track_input_device(mut controls: ResMut<Controls>) {
+    if get_last_key_pressed().is_some() {
+        controls.device = InputDevice::Keyboard;
+    } else if is_mouse_button_pressed(MouseButton::Left) || mouse_delta_position() != Vec2::ZERO {
+        controls.device = InputDevice::Mouse;
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_toggle_fullscreen(controls: Res<Controls>, mut display: ResMut<Display>) {
+    if controls.is_pressed(Action::Fullscreen) {
+        display.fullscreen = !display.fullscreen;
+        set_fullscreen(display.fullscreen);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_skip_title(controls: Res<Controls>, mut game_state: ResMut<GameState>) {
+    if controls.is_released(Action::Continue)
+        || is_mouse_button_pressed(MouseButton::Left)
+        || get_time() > 5.0
     {
-        draw_title_screen_text(heading_font_value, body_font_value, body_italic_font_value);
+        game_state.queue(GameMode::Menu);
+    }
+}
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_request_quit(controls: Res<Controls>, mut game_state: ResMut<GameState>) {
+    if controls.is_released(Action::Back) || is_quit_requested() {
+        request_quit(&mut game_state);
+    }
+}
+
+/// Opens the quit confirmation over the current mode.
+fn request_quit(game_state: &mut GameState) {
+    let beneath = game_state.mode().clone();
+    game_state.queue(GameMode::Exiting(Box::new(beneath)));
+}
+
+/// Whether the player confirmed `item` in the menu this frame.  Reads every message, so none
+/// are left over for the next frame.
+fn menu_item_confirmed(confirmed: &mut MessageReader<MenuConfirmed>, item: MenuItem) -> bool {
+    let mut result = false;
+    for value in confirmed.read() {
+        result |= value.item == item;
+    }
+
+    result
+}
+
+/// Menu inputs from the keyboard, gamepads and mouse this frame, in the order to apply them.
+fn menu_inputs(menu: &MenuNavigation, controls: &Controls, display: &Display) -> Vec<MenuInput> {
+    let pointer = display.letterbox.to_virtual(mouse_position().into());
+    let hovered = menu.item_at(pointer);
+    let clicked = hovered.filter(|_| is_mouse_button_pressed(MouseButton::Left));
+
+    [
+        controls.is_pressed(Action::Up).then_some(MenuInput::Up),
+        controls.is_pressed(Action::Down).then_some(MenuInput::Down),
+        // only follow the mouse once it moves, so it does not fight the keyboard for focus
+        hovered
+            .filter(|_| clicked.is_some() || mouse_delta_position() != Vec2::ZERO)
+            .map(MenuInput::Hover),
+        (controls.is_released(Action::Confirm)
+            || controls.is_released(Action::Continue)
+            || clicked.is_some())
+        .then_some(MenuInput::Confirm),
+        controls
+            .is_released(Action::Back)
+            .then_some(MenuInput::Back),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn play_menu_sound(sound: Option<&Sound>) {
+    if let Some(value) = sound {
+        play_sound_once(value);
     }
 }
 
+/// Moves focus around the menu and between its pages, handing confirmed actions on to other
+/// systems as `MenuConfirmed` messages.
+#[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+fn handle_menu_navigation(
+    campaign: Res<Campaign>,
+    controls: Res<Controls>,
+    current_level: Res<CurrentLevel>,
+    display: Res<Display>,
+    game_assets: Res<GameAssets>,
+    play_mode: Res<PlayMode>,
+    mut game_state: ResMut<GameState>,
+    mut menu: ResMut<MenuNavigation>,
+    mut confirmed: MessageWriter<MenuConfirmed>,
+) {
+    let sounds = &game_assets.sounds;
+    for input in menu_inputs(&menu, &controls, &display) {
+        let items = menu.page.items(campaign.levels.len(), *play_mode);
+        match menu.navigate(input, &items) {
+            MenuOutcome::Idle => {}
+            MenuOutcome::FocusMoved => play_menu_sound(sounds.flap.as_ref()),
+            MenuOutcome::PageChanged => {
+                if menu.page == MenuPage::LevelSelect && *play_mode != PlayMode::Daily {
+                    menu.focus = current_level.index;
+                }
+                play_menu_sound(sounds.pickup.as_ref());
+            }
+            MenuOutcome::Confirmed(item) => {
+                play_menu_sound(sounds.pickup.as_ref());
+                confirmed.write(MenuConfirmed { item });
+            }
+            MenuOutcome::Quit => request_quit(&mut game_state),
+        }
+    }
+    if is_quit_requested() {
+        request_quit(&mut game_state);
+    }
+}
+
+/// Opens the main page whenever the game enters the menu.
+fn reset_menu_navigation(mut menu: ResMut<MenuNavigation>) {
+    *menu = MenuNavigation::default();
+}
+
 #[allow(clippy::needless_pass_by_value)]
-fn update_exit_ui(game_assets: Res<GameAssets>) {
-    let GameAssets {
-        fonts: GameFonts {
-            body: body_font, ..
-        },
-        ..
-    } = game_assets.into_inner();
-    if let Some(value) = body_font {
-        draw_exit_screen_text(value);
+pub fn handle_toggle_lives(
+    controls: Res<Controls>,
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut lives_settings: ResMut<LivesSettings>,
+) {
+    if menu_item_confirmed(&mut confirmed, MenuItem::Lives) || controls.is_pressed(Action::Lives) {
+        lives_settings.enabled = !lives_settings.enabled;
     }
 }
 
+/// Selects the focused level on the level select page, once it is unlocked.
 #[allow(clippy::needless_pass_by_value)]
-fn update_ui(
-    query: Query<&Score, With<Flipper>>,
+pub fn handle_level_select(
+    campaign: Res<Campaign>,
+    menu: Res<MenuNavigation>,
+    progress: Res<Progress>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut practice_settings: ResMut<PracticeSettings>,
+) {
+    if menu.page != MenuPage::LevelSelect {
+        return;
+    }
+    let index = menu.focus;
+    if index != current_level.index
+        && index < campaign.levels.len()
+        && progress.status(index) != LevelStatus::Locked
+    {
+        current_level.index = index;
+        practice_settings.start_obstacle = 0;
+    }
+}
+
+/// Picks the obstacle practice runs start from, for the selected level.
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_practice_start_select(
+    campaign: Res<Campaign>,
+    controls: Res<Controls>,
+    current_level: Res<CurrentLevel>,
+    play_mode: Res<PlayMode>,
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut practice_settings: ResMut<PracticeSettings>,
+) {
+    let chosen = menu_item_confirmed(&mut confirmed, MenuItem::PracticeStart);
+    if *play_mode != PlayMode::Practice {
+        return;
+    }
+    let obstacle_count = campaign
+        .levels
+        .get(current_level.index)
+        .map_or(0, |level| level.obstacles.len());
+    if controls.is_pressed(Action::PracticeEarlier) {
+        practice_settings.start_obstacle = practice_settings.start_obstacle.saturating_sub(1);
+    } else if controls.is_pressed(Action::PracticeLater) {
+        practice_settings.start_obstacle =
+            (practice_settings.start_obstacle + 1).min(obstacle_count);
+    } else if chosen {
+        // the menu item cycles back round to the level start
+        practice_settings.start_obstacle =
+            (practice_settings.start_obstacle + 1) % (obstacle_count + 1);
+    }
+}
+
+/// Cycles the practice time scale, from the menu or mid-run.
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_practice_time_scale(
+    controls: Res<Controls>,
+    play_mode: Res<PlayMode>,
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut practice_settings: ResMut<PracticeSettings>,
+) {
+    let chosen = menu_item_confirmed(&mut confirmed, MenuItem::PracticeSpeed);
+    if *play_mode == PlayMode::Practice && (controls.is_pressed(Action::PracticeSpeed) || chosen) {
+        practice_settings.cycle_time_scale();
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_toggle_play_mode(
+    controls: Res<Controls>,
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut play_mode: ResMut<PlayMode>,
+) {
+    if menu_item_confirmed(&mut confirmed, MenuItem::PlayMode) || controls.is_pressed(Action::Mode)
+    {
+        *play_mode = play_mode.next();
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_toggle_difficulty(
+    controls: Res<Controls>,
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut difficulty: ResMut<Difficulty>,
+) {
+    if menu_item_confirmed(&mut confirmed, MenuItem::Difficulty)
+        || controls.is_pressed(Action::Difficulty)
+    {
+        *difficulty = difficulty.next();
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_start_game(
+    play_mode: Res<PlayMode>,
+    progress: Res<Progress>,
+    two_player_settings: Res<TwoPlayerSettings>,
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut daily_challenge: ResMut<DailyChallenge>,
+    mut game_state: ResMut<GameState>,
+) {
+    // picking a level only starts it once it is selected, so locked levels stay closed
+    let level = current_level.index;
+    let mut start = false;
+    for MenuConfirmed { item } in confirmed.read() {
+        start |= *item == MenuItem::Play || *item == MenuItem::Level(level);
+    }
+    if start && progress.status(level) != LevelStatus::Locked {
+        // every run starts from a freshly spawned world
+        current_level.spawned = false;
+        if *play_mode == PlayMode::Daily {
+            daily_challenge.refresh(UtcDate::today());
+            if !two_player_settings.enabled {
+                daily_challenge.attempts += 1;
+            }
+            daily_challenge.save(&save_path(DAILY_FILE_NAME));
+        }
+        game_state.queue(GameMode::Playing);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_toggle_two_player(
+    controls: Res<Controls>,
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut two_player_settings: ResMut<TwoPlayerSettings>,
+) {
+    if menu_item_confirmed(&mut confirmed, MenuItem::Players)
+        || controls.is_pressed(Action::Players)
+    {
+        two_player_settings.enabled = !two_player_settings.enabled;
+    }
+}
+
+/// Switches to the next colour palette and saves the choice for the next session.  Run
+/// entities take their colours when spawned, so the course picks the palette up from the next
+/// run.
+pub fn handle_change_palette(
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut preferences: ResMut<Preferences>,
+    mut theme: ResMut<Theme>,
+) {
+    if menu_item_confirmed(&mut confirmed, MenuItem::Palette) {
+        *theme = Theme::new(theme.palette().next());
+        preferences.palette = theme.palette();
+        preferences.save(&save_path(PREFERENCES_FILE_NAME));
+    }
+}
+
+/// Switches to the next locale and saves the choice for the next session.  The main loop picks
+/// up any font the new locale's script needs.
+pub fn handle_change_locale(
+    mut confirmed: MessageReader<MenuConfirmed>,
+    mut preferences: ResMut<Preferences>,
+    mut strings: ResMut<Strings>,
+) {
+    if menu_item_confirmed(&mut confirmed, MenuItem::Language) {
+        *strings = Strings::new(strings.locale().next());
+        preferences.locale = strings.locale();
+        preferences.save(&save_path(PREFERENCES_FILE_NAME));
+    }
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+fn update_game_over_ui(
+    query: Query<(&Player, &Score), With<Flipper>>,
+    controls: Res<Controls>,
+    daily_challenge: Res<DailyChallenge>,
+    difficulty: Res<Difficulty>,
     game_assets: Res<GameAssets>,
+    play_mode: Res<PlayMode>,
+    race_result: Res<RaceResult>,
+    strings: Res<Strings>,
+    theme: Res<Theme>,
 ) -> bevy_ecs::error::Result<()> {
-    let score = query.single()?.value;
-    let assets = game_assets.into_inner();
-
-    let GameAssets {
-        fonts: GameFonts {
-            body: body_font, ..
-        },
-        ..
-    } = assets;
-    if let Some(value) = body_font {
-        draw_info_text(score, value);
+    let fonts = &game_assets.fonts;
+    if query.iter().count() > 1 {
+        draw_race_results_text(
+            &race_results_text(&player_results(&query), race_result.winner, &strings),
+            &theme.style(&theme.game_over),
+            &controls,
+            &strings,
+            fonts,
+        );
+        return Ok(());
     }
 
+    let (_, score) = query.single()?;
+    let daily = (*play_mode == PlayMode::Daily)
+        .then(|| daily_challenge_text(&daily_challenge, *difficulty, &strings));
+    draw_game_over_screen_text(score, daily.as_ref(), &controls, &strings, &theme, fonts);
+
     Ok(())
 }
 
-#[allow(clippy::needless_pass_by_value)]
-fn update_win_screen_ui(game_assets: Res<GameAssets>) {
-    let GameAssets {
-        fonts: GameFonts {
-            body: body_font, ..
+/// Each player's score, in player order, for the race results screen.
+fn player_results<'a>(
+    query: &'a Query<(&Player, &Score), With<Flipper>>,
+) -> Vec<(Player, &'a Score)> {
+    let mut result: Vec<(Player, &Score)> = query
+        .iter()
+        .map(|(player, score)| (*player, score))
+        .collect();
+    result.sort_by_key(|(player, _)| player.number);
+
+    result
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+fn update_menu_ui(
+    campaign: Res<Campaign>,
+    controls: Res<Controls>,
+    current_level: Res<CurrentLevel>,
+    difficulty: Res<Difficulty>,
+    game_assets: Res<GameAssets>,
+    lives_settings: Res<LivesSettings>,
+    play_mode: Res<PlayMode>,
+    daily_challenge: Res<DailyChallenge>,
+    practice_settings: Res<PracticeSettings>,
+    progress: Res<Progress>,
+    strings: Res<Strings>,
+    theme: Res<Theme>,
+    two_player_settings: Res<TwoPlayerSettings>,
+    mut menu: ResMut<MenuNavigation>,
+) {
+    let best_score = |index: usize, difficulty: Difficulty| {
+        progress
+            .levels
+            .get(index)
+            .and_then(|value| value.best_score(difficulty))
+    };
+    let levels: Vec<LevelSummary> = campaign
+        .levels
+        .iter()
+        .enumerate()
+        .map(|(index, level)| LevelSummary {
+            name: strings.get(&level.message_id),
+            status: progress.status(index),
+            best_score: best_score(index, *difficulty),
+        })
+        .collect();
+    let view = match menu.page {
+        MenuPage::Main => MenuView::Main,
+        MenuPage::LevelSelect => MenuView::LevelSelect {
+            levels: &levels,
+            selected: current_level.index,
+            daily: (*play_mode == PlayMode::Daily)
+                .then(|| daily_challenge_text(&daily_challenge, *difficulty, &strings)),
         },
-        ..
-    } = game_assets.into_inner();
-    if let Some(value) = body_font {
-        draw_win_screen_text(value);
+        MenuPage::Settings => MenuView::Settings(MenuSettings {
+            play_mode: *play_mode,
+            difficulty: *difficulty,
+            lives_enabled: lives_settings.enabled,
+            two_player: two_player_settings.enabled,
+            practice: (*play_mode == PlayMode::Practice).then(|| {
+                practice_settings_text(
+                    practice_settings.start_obstacle,
+                    practice_settings.time_scale(),
+                    &controls,
+                    &strings,
+                )
+            }),
+            language: strings.locale_name(),
+            palette: theme.palette(),
+        }),
+        MenuPage::HighScores => {
+            let [_, daily_best] = daily_challenge_text(&daily_challenge, *difficulty, &strings);
+            MenuView::HighScores(
+                campaign
+                    .levels
+                    .iter()
+                    .enumerate()
+                    .map(|(index, level)| {
+                        high_score_text(
+                            strings.get(&level.message_id),
+                            Difficulty::ALL.map(|value| best_score(index, value)),
+                            &strings,
+                        )
+                    })
+                    .chain(std::iter::once(
+                        strings.format("high-scores-daily", &[("summary", &daily_best)]),
+                    ))
+                    .collect(),
+            )
+        }
+        MenuPage::Credits => MenuView::Credits,
+    };
+    let items = menu.page.items(campaign.levels.len(), *play_mode);
+    menu.item_areas = draw_menu_screen_text(
+        &view,
+        &items,
+        menu.focus,
+        &controls,
+        &strings,
+        &theme,
+        &game_assets.fonts,
+    );
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn update_title_ui(game_assets: Res<GameAssets>, strings: Res<Strings>, theme: Res<Theme>) {
+    draw_title_screen_text(&strings, &theme, &game_assets.fonts);
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn update_exit_ui(
+    controls: Res<Controls>,
+    game_assets: Res<GameAssets>,
+    strings: Res<Strings>,
+    theme: Res<Theme>,
+) {
+    draw_overlay_veil(&theme);
+    draw_exit_screen_text(&controls, &strings, &theme, &game_assets.fonts);
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn update_ui(
+    query: Query<
+        (
+            &Player,
+            &Score,
+            &Combo,
+            &Position,
+            Option<&Lives>,
+            Option<&TimeTrial>,
+            Option<&Shield>,
+            Option<&SlowMotion>,
+            Option<&Magnet>,
+            Has<Eliminated>,
+        ),
+        With<Flipper>,
+    >,
+    controls: Res<Controls>,
+    game_assets: Res<GameAssets>,
+    level: Res<Level>,
+    personal_best: PersonalBest,
+    strings: Res<Strings>,
+    theme: Res<Theme>,
+) {
+    let mut players: Vec<_> = query.iter().collect();
+    players.sort_by_key(|(player, ..)| player.number);
+    let fonts = &game_assets.fonts;
+    if let [(_, score, combo, position, lives, time_trial, shield, slow_motion, magnet, _)] =
+        players[..]
+    {
+        let status = HudStatus {
+            score,
+            lives: lives.map(|value| value.remaining),
+            best_score: personal_best.score(),
+            combo,
+            distance: (position.x - FLIPPER_START_X).max(0.0) / PIXELS_PER_METRE,
+            course_length: (level.finish_line_x - FLIPPER_START_X) / PIXELS_PER_METRE,
+            time_trial: time_trial.map(|value| (value, &level.medal_times)),
+            power_ups: &power_up_timers(shield, slow_motion, magnet),
+        };
+        draw_info_text(&status, &controls, &strings, &theme, fonts);
+        return;
     }
+
+    let player_lines: Vec<String> = players
+        .iter()
+        .map(
+            |(player, score, _, _, lives, _, shield, slow_motion, magnet, eliminated)| {
+                player_info_text(
+                    **player,
+                    score,
+                    lives.map(|value| value.remaining),
+                    *eliminated,
+                    &power_up_timers(*shield, *slow_motion, *magnet),
+                    &strings,
+                )
+            },
+        )
+        .collect();
+    // both players start together, so share one clock
+    let time_trial = players
+        .iter()
+        .find_map(|(_, _, _, _, _, time_trial, ..)| *time_trial)
+        .map(|value| (value, &level.medal_times));
+    draw_players_info_text(&player_lines, time_trial, &strings, &theme, fonts);
+}
+
+/// Message ids of active power-ups with their seconds remaining, for the HUD.
+fn power_up_timers(
+    shield: Option<&Shield>,
+    slow_motion: Option<&SlowMotion>,
+    magnet: Option<&Magnet>,
+) -> Vec<(&'static str, f32)> {
+    [
+        shield.map(|value| ("power-up-shield", value.remaining)),
+        slow_motion.map(|value| ("power-up-slow", value.remaining)),
+        magnet.map(|value| ("power-up-magnet", value.remaining)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+fn update_win_screen_ui(
+    query: Query<(&Player, &Score), With<Flipper>>,
+    time_trial_query: Query<&TimeTrial, With<Flipper>>,
+    controls: Res<Controls>,
+    daily_challenge: Res<DailyChallenge>,
+    difficulty: Res<Difficulty>,
+    game_assets: Res<GameAssets>,
+    level: Res<Level>,
+    play_mode: Res<PlayMode>,
+    race_result: Res<RaceResult>,
+    strings: Res<Strings>,
+    theme: Res<Theme>,
+) -> bevy_ecs::error::Result<()> {
+    let fonts = &game_assets.fonts;
+    if query.iter().count() > 1 {
+        draw_race_results_text(
+            &race_results_text(&player_results(&query), race_result.winner, &strings),
+            &theme.style(&theme.won),
+            &controls,
+            &strings,
+            fonts,
+        );
+        return Ok(());
+    }
+
+    let (_, score) = query.single()?;
+    let daily = (*play_mode == PlayMode::Daily)
+        .then(|| daily_challenge_text(&daily_challenge, *difficulty, &strings));
+    let time_trial_result = time_trial_query
+        .iter()
+        .next()
+        .map(|TimeTrial { elapsed, .. }| (*elapsed, level.medal_times.medal(*elapsed)));
+    draw_win_screen_text(
+        score,
+        time_trial_result,
+        daily.as_ref(),
+        &controls,
+        &strings,
+        &theme,
+        fonts,
+    );
+
+    Ok(())
 }
 pub fn create_playing_schedule() -> Schedule {
     let mut result = Schedule::default();
     result
-        .add_systems(update_ui)
         .add_systems(handle_request_quit)
-        .add_systems(update_delta_time)
-        .add_systems(update_camera)
-        .add_systems(draw_rectangles)
-        .add_systems(draw_obstacles)
-        .add_systems(handle_flipper_controls)
-        .add_systems(update_flipper_vertical_position)
-        .add_systems(handle_obstacle_flipper_collision)
-        .add_systems(handle_flipper_finish_line_collision)
-        .add_systems(update_positions);
+        .add_systems(handle_practice_time_scale)
+        .add_systems(
+            (
+                restart_run,
+                place_practice_start,
+                update_delta_time,
+                store_previous_positions,
+                handle_speed_controls,
+                update_camera,
+                handle_flipper_controls,
+                update_flipper_vertical_position,
+                update_obstacle_motion,
+                apply_magnet,
+                update_positions,
+                update_time_trial,
+                message_update_system,
+                detect_collisions,
+                (
+                    handle_obstacle_flipper_collision,
+                    handle_flipper_collectible_collision,
+                    handle_flipper_power_up_collision,
+                    handle_flipper_checkpoint_collision,
+                    handle_flipper_finish_line_collision,
+                ),
+                handle_flipper_hit,
+                update_pickup_effects,
+                (
+                    update_timed_effect::<Invulnerable>,
+                    update_timed_effect::<Magnet>,
+                    update_timed_effect::<Shield>,
+                    update_timed_effect::<SlowMotion>,
+                ),
+                despawn_passed_entities,
+            )
+                .chain(),
+        );
+
+    result
+}
+
+/// Draws the run, separately from its updates so the run stays visible, frozen, beneath an
+/// overlay.  The HUD goes down last, over the band the course leaves clear for it.
+#[must_use]
+pub fn create_playing_draw_schedule() -> Schedule {
+    let mut result = Schedule::default();
+    result.add_systems(
+        (
+            draw_rectangles,
+            draw_obstacles,
+            draw_predicted_trajectory,
+            update_ui,
+        )
+            .chain(),
+    );
+
+    result
+}
+
+pub fn create_display_schedule() -> Schedule {
+    let mut result = Schedule::default();
+    result
+        .add_systems((poll_gamepads, track_input_device).chain())
+        .add_systems(handle_toggle_fullscreen);
 
     result
 }
@@ -532,18 +2059,47 @@ pub fn create_exiting_schedule() -> Schedule {
 pub fn create_title_schedule() -> Schedule {
     let mut result = Schedule::default();
     result
-        .add_systems(update_title_ui)
         .add_systems(handle_skip_title)
         .add_systems(handle_request_quit);
     result
 }
 
+pub fn create_title_draw_schedule() -> Schedule {
+    let mut result = Schedule::default();
+    result.add_systems(update_title_ui);
+
+    result
+}
+
+#[must_use]
 pub fn create_menu_schedule() -> Schedule {
     let mut result = Schedule::default();
+    result.add_systems(
+        (
+            message_update_system,
+            handle_menu_navigation,
+            handle_level_select,
+            (
+                handle_practice_start_select,
+                handle_practice_time_scale,
+                handle_toggle_play_mode,
+                handle_toggle_difficulty,
+                handle_toggle_lives,
+                handle_toggle_two_player,
+                handle_change_locale,
+                handle_change_palette,
+                handle_start_game,
+            ),
+        )
+            .chain(),
+    );
+
     result
-        .add_systems(update_menu_ui)
-        .add_systems(handle_start_game)
-        .add_systems(handle_request_quit);
+}
+
+pub fn create_menu_draw_schedule() -> Schedule {
+    let mut result = Schedule::default();
+    result.add_systems(update_menu_ui);
 
     result
 }
@@ -551,28 +2107,593 @@ pub fn create_menu_schedule() -> Schedule {
 pub fn create_victory_schedule() -> Schedule {
     let mut result = Schedule::default();
     result
-        .add_systems(update_win_screen_ui)
         .add_systems(handle_request_quit)
         .add_systems(handle_replay);
 
     result
 }
 
+pub fn create_victory_draw_schedule() -> Schedule {
+    let mut result = Schedule::default();
+    result.add_systems(update_win_screen_ui);
+
+    result
+}
+
 pub fn create_game_over_schedule() -> Schedule {
     let mut result = Schedule::default();
     result
-        .add_systems(update_game_over_ui)
         .add_systems(handle_request_quit)
         .add_systems(handle_replay);
 
     result
 }
 
+pub fn create_game_over_draw_schedule() -> Schedule {
+    let mut result = Schedule::default();
+    result.add_systems(update_game_over_ui);
+
+    result
+}
+
+/// Starts the background music for a run; it keeps playing beneath the quit prompt.
+#[allow(clippy::needless_pass_by_value)]
+fn start_background_music(game_assets: Res<GameAssets>) {
+    if let Some(value) = &game_assets.sounds.background {
+        start_playing_looped_sound(value);
+    }
+}
+
+/// Stops the background music once a run ends.
+#[allow(clippy::needless_pass_by_value)]
+fn stop_background_music(game_assets: Res<GameAssets>) {
+    if let Some(value) = &game_assets.sounds.background {
+        stop_playing_looped_sound(value);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn play_game_over_sound(game_assets: Res<GameAssets>) {
+    if let Some(value) = &game_assets.sounds.game_over {
+        play_sound_once(value);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn play_victory_sound(game_assets: Res<GameAssets>) {
+    if let Some(value) = &game_assets.sounds.victory {
+        play_sound_once(value);
+    }
+}
+
+pub fn create_transition_hooks() -> TransitionHooks {
+    let mut enter_playing = Schedule::default();
+    enter_playing.add_systems(start_background_music);
+
+    let mut exit_playing = Schedule::default();
+    exit_playing.add_systems(stop_background_music);
+
+    let mut enter_game_over = Schedule::default();
+    enter_game_over
+        .add_systems(play_game_over_sound)
+        .add_systems(remove_power_ups);
+
+    let mut enter_won = Schedule::default();
+    enter_won
+        .add_systems(play_victory_sound)
+        .add_systems(remove_power_ups);
+
+    let mut enter_menu = Schedule::default();
+    enter_menu.add_systems(reset_menu_navigation);
+
+    TransitionHooks::default()
+        .on_enter(&GameMode::Menu, enter_menu)
+        .on_enter(&GameMode::Playing, enter_playing)
+        .on_exit(&GameMode::Playing, exit_playing)
+        .on_enter(&GameMode::GameOver, enter_game_over)
+        .on_enter(&GameMode::Won, enter_won)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::systems::FlipperObstacleCollision;
+    use crate::systems::{FlipperObstacleCollision, VerticalBoundary, flipper_vertical_boundary};
+
+    use super::{
+        Camera, Campaign, ClearedBy, Collectible, CollectibleKind, CollectibleSpec,
+        CollisionStarted, Colour, Controls, CurrentLevel, DailyChallenge, DeltaTime, Difficulty,
+        Flipper, FlipperHit, FlipperHitCause, GameAssets, GameState, Hitbox, Invulnerable, Lives,
+        Magnet, ObstacleShape, PickupEffect, PlayMode, Position, PowerUpKind, PowerUpSpec,
+        PreviousPosition, Progress, RaceResult, RectangleShape, RespawnPoint, Score, Shield,
+        SlowMotion, Theme, TwoPlayerSettings, Velocity, apply_magnet, collectible_bundle,
+        despawn_passed_entities, flap, flipper_bundle, handle_flipper_collectible_collision,
+        handle_flipper_hit, handle_flipper_power_up_collision, handle_obstacle_flipper_collision,
+        last_player_out, local_player, obstacle_flipper_collision, power_up_bundle,
+        predict_trajectory, update_flipper_vertical_position, update_pickup_effects,
+        update_timed_effect,
+    };
+    use crate::{
+        collision::{Collider, CollisionEnded, CollisionPair, Contacts, detect_collisions},
+        components::{HorizontalExtent, Placed},
+        daily::UtcDate,
+        display::{HUD_BAND_HEIGHT, VIRTUAL_HEIGHT},
+        resources::CourseIndex,
+    };
+    use bevy_ecs::{
+        entity::Entity,
+        message::{MessageRegistry, Messages},
+        query::With,
+        schedule::{IntoScheduleConfigs, Schedule},
+        system::{Query, Res},
+        world::World,
+    };
+    use proptest::prelude::*;
+
+    /// World holding the resources run systems read, with a single-player flipper on two lives.
+    fn run_world() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<GameAssets>();
+        world.init_resource::<Camera>();
+        world.init_resource::<GameState>();
+        world.init_resource::<RaceResult>();
+        world.init_resource::<Campaign>();
+        world.init_resource::<CurrentLevel>();
+        world.init_resource::<Difficulty>();
+        world.init_resource::<PlayMode>();
+        world.init_resource::<TwoPlayerSettings>();
+        let level_count = world.resource::<Campaign>().levels.len();
+        world.insert_resource(Progress::new(level_count));
+        world.insert_resource(DailyChallenge::new(UtcDate::from_days_since_epoch(0)));
+        MessageRegistry::register_message::<CollisionStarted>(&mut world);
+        MessageRegistry::register_message::<FlipperHit>(&mut world);
+        let player_one = local_player(1, &Controls::default());
+        let [colour, _] = Theme::default().flippers;
+        let flipper = world
+            .spawn((flipper_bundle(player_one, colour), Lives { remaining: 2 }))
+            .id();
+
+        (world, flipper)
+    }
+
+    /// Flipper which cleared an obstacle at x = 400, then lost a life further on and went back
+    /// to its start.
+    fn respawned_world() -> (World, Entity, Entity) {
+        let (mut world, flipper) = run_world();
+        let player_one = local_player(1, &Controls::default());
+        let mut cleared_by = ClearedBy::default();
+        cleared_by.insert(player_one);
+        let obstacle = world
+            .spawn((
+                Position { x: 400.0, y: 0.0 },
+                ObstacleShape {
+                    gap_length: 200.0,
+                    gap_y_displacement: 150.0,
+                    width: 15.0,
+                },
+                cleared_by,
+            ))
+            .id();
+        world.entity_mut(flipper).get_mut::<Position>().unwrap().x = 600.0;
+        world.write_message(FlipperHit {
+            flipper,
+            cause: FlipperHitCause::Obstacle,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(handle_flipper_hit);
+        schedule.run(&mut world);
+
+        (world, flipper, obstacle)
+    }
+
+    /// Moves the flipper to height `y` in line with the obstacle, and has the two collide.
+    fn cross_obstacle(world: &mut World, flipper: Entity, obstacle: Entity, y: f32) {
+        let position = Position { x: 395.0, y };
+        world
+            .entity_mut(flipper)
+            .insert((PreviousPosition(position.clone()), position));
+        world.resource_mut::<Messages<FlipperHit>>().clear();
+        world.write_message(CollisionStarted {
+            pair: CollisionPair::new(flipper, obstacle),
+            time: 1.0,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(handle_obstacle_flipper_collision);
+        schedule.run(world);
+    }
+
+    #[test]
+    fn respawned_flipper_hits_wall_of_obstacle_cleared_before_losing_life() {
+        // arrange
+        let (mut world, flipper, obstacle) = respawned_world();
+        world.entity_mut(flipper).remove::<Invulnerable>();
+
+        // act
+        cross_obstacle(&mut world, flipper, obstacle, 20.0);
+
+        // assert
+        assert_eq!(world.resource::<Messages<FlipperHit>>().len(), 1);
+    }
+
+    #[test]
+    fn respawned_flipper_scores_again_for_obstacle_cleared_before_losing_life() {
+        // arrange
+        let (mut world, flipper, obstacle) = respawned_world();
+
+        // act
+        cross_obstacle(&mut world, flipper, obstacle, 240.0);
+
+        // assert
+        assert_eq!(world.get::<Score>(flipper).unwrap().obstacles_cleared, 1);
+        assert!(
+            world
+                .get::<ClearedBy>(obstacle)
+                .unwrap()
+                .contains(local_player(1, &Controls::default()))
+        );
+    }
+
+    /// Runs one frame of collectible pickups, with `flippers` each touching `collectible`.
+    fn touch_collectible(world: &mut World, flippers: &[Entity], collectible: Entity) {
+        for flipper in flippers {
+            world.write_message(CollisionStarted {
+                pair: CollisionPair::new(*flipper, collectible),
+                time: 1.0,
+            });
+        }
+        let mut schedule = Schedule::default();
+        schedule.add_systems(handle_flipper_collectible_collision);
+        schedule.run(world);
+        world.resource_mut::<Messages<CollisionStarted>>().update();
+    }
+
+    #[test]
+    fn collecting_a_pearl_scores_its_value_once() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        let spec = CollectibleSpec::new(CollectibleKind::Pearl, 100.0, 290.0);
+        let pearl = world
+            .spawn(collectible_bundle(&spec, &Theme::default()))
+            .id();
+
+        // act
+        touch_collectible(&mut world, &[flipper], pearl);
+        touch_collectible(&mut world, &[flipper], pearl);
+
+        // assert
+        let score = world.get::<Score>(flipper).unwrap();
+        assert_eq!((score.value, score.items_collected), (spec.value, 1));
+        assert!(world.get::<Collectible>(pearl).is_none());
+        assert!(world.get::<PickupEffect>(pearl).is_some());
+    }
+
+    #[test]
+    fn players_reaching_a_shell_together_score_it_once() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        let [_, colour] = Theme::default().flippers;
+        let rival = world
+            .spawn(flipper_bundle(
+                local_player(2, &Controls::default()),
+                colour,
+            ))
+            .id();
+        let spec = CollectibleSpec::new(CollectibleKind::Shell, 100.0, 300.0);
+        let shell = world
+            .spawn(collectible_bundle(&spec, &Theme::default()))
+            .id();
+
+        // act
+        touch_collectible(&mut world, &[flipper, rival], shell);
+
+        // assert
+        let result: Vec<(u32, u32)> = [flipper, rival]
+            .map(|entity| {
+                let score = world.get::<Score>(entity).unwrap();
+                (score.value, score.items_collected)
+            })
+            .into_iter()
+            .collect();
+        assert_eq!(result, [(spec.value, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn pickup_effect_grows_and_fades_then_despawns() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        let spec = CollectibleSpec::new(CollectibleKind::Pearl, 100.0, 290.0);
+        let pearl = world
+            .spawn(collectible_bundle(&spec, &Theme::default()))
+            .id();
+        touch_collectible(&mut world, &[flipper], pearl);
+        world.insert_resource(DeltaTime {
+            seconds: 0.2,
+            unscaled_seconds: 0.2,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_pickup_effects);
+
+        // act
+        schedule.run(&mut world);
+        let fading = (
+            world.get::<RectangleShape>(pearl).unwrap().width,
+            world.get::<Colour>(pearl).unwrap().a,
+        );
+        schedule.run(&mut world);
+
+        // assert
+        assert!(fading.0 > 10.0 && fading.1 < 255, "{fading:?}");
+        assert!(world.get_entity(pearl).is_err());
+    }
+
+    #[test]
+    fn shield_absorbs_exactly_one_obstacle_hit() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        let spec = PowerUpSpec {
+            kind: PowerUpKind::Shield,
+            x: 100.0,
+            y: 290.0,
+        };
+        let power_up = world.spawn(power_up_bundle(&spec, &Theme::default())).id();
+        world.write_message(CollisionStarted {
+            pair: CollisionPair::new(flipper, power_up),
+            time: 1.0,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(handle_flipper_power_up_collision);
+        schedule.run(&mut world);
+        world.resource_mut::<Messages<CollisionStarted>>().update();
+        let shielded = world.get::<Shield>(flipper).is_some();
+        // two overlapping obstacles, both entered through the wall in the same frame
+        let obstacles = [0, 1].map(|_| {
+            world
+                .spawn((
+                    Position { x: 400.0, y: 0.0 },
+                    ObstacleShape {
+                        gap_length: 200.0,
+                        gap_y_displacement: 150.0,
+                        width: 15.0,
+                    },
+                    ClearedBy::default(),
+                ))
+                .id()
+        });
+        let position = Position { x: 395.0, y: 20.0 };
+        world
+            .entity_mut(flipper)
+            .insert((PreviousPosition(position.clone()), position));
+        for obstacle in obstacles {
+            world.write_message(CollisionStarted {
+                pair: CollisionPair::new(flipper, obstacle),
+                time: 1.0,
+            });
+        }
+        let mut schedule = Schedule::default();
+        schedule.add_systems(handle_obstacle_flipper_collision);
 
-    use super::{ObstacleShape, Position, RectangleShape, obstacle_flipper_collision};
+        // act
+        schedule.run(&mut world);
+
+        // assert
+        assert!(shielded);
+        assert_eq!(world.resource::<Messages<FlipperHit>>().len(), 1);
+        assert!(world.get::<Shield>(flipper).is_none());
+    }
+
+    #[test]
+    fn magnet_pulls_only_collectibles_in_range() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        world.entity_mut(flipper).insert((
+            Position { x: 20.0, y: 290.0 },
+            Magnet {
+                remaining: 6.0,
+                radius: 150.0,
+                speed: 360.0,
+            },
+        ));
+        let [near, far] = [95.0, 295.0].map(|x| {
+            let spec = CollectibleSpec::new(CollectibleKind::Pearl, x, 295.0);
+            world
+                .spawn(collectible_bundle(&spec, &Theme::default()))
+                .id()
+        });
+        world.insert_resource(DeltaTime {
+            seconds: 0.1,
+            unscaled_seconds: 0.1,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(apply_magnet);
+
+        // act
+        schedule.run(&mut world);
+
+        // assert
+        let near_position = world.get::<Position>(near).unwrap();
+        assert!((near_position.x - 59.0).abs() < 1e-3, "{near_position:?}");
+        assert!((near_position.y - 295.0).abs() < 1e-3, "{near_position:?}");
+        let far_position = world.get::<Position>(far).unwrap();
+        assert!((far_position.x - 295.0).abs() < 1e-6, "{far_position:?}");
+    }
+
+    #[test]
+    fn timed_effect_runs_out_in_real_time_during_slow_motion() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        world.entity_mut(flipper).insert(SlowMotion {
+            remaining: 1.0,
+            time_scale: 0.5,
+        });
+        world.insert_resource(DeltaTime {
+            seconds: 0.3,
+            unscaled_seconds: 0.6,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_timed_effect::<SlowMotion>);
+
+        // act
+        schedule.run(&mut world);
+        let remaining = world.get::<SlowMotion>(flipper).unwrap().remaining;
+        schedule.run(&mut world);
+
+        // assert
+        assert!((remaining - 0.4).abs() < 1e-6, "{remaining}");
+        assert!(world.get::<SlowMotion>(flipper).is_none());
+    }
+
+    #[test]
+    fn broad_phase_finds_placed_obstacle_beside_flipper_through_course_index() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        world.init_resource::<Contacts>();
+        MessageRegistry::register_message::<CollisionEnded>(&mut world);
+        let mut course_index = CourseIndex::default();
+        let [beside, _ahead] = [25.0, 4_000.0].map(|x| {
+            let position = Position { x, y: 0.0 };
+            let shape = ObstacleShape {
+                gap_length: 200.0,
+                gap_y_displacement: 150.0,
+                width: 15.0,
+            };
+            let (left, right) = shape.horizontal_extent(&position);
+            let obstacle = world
+                .spawn((
+                    position,
+                    shape,
+                    Collider::rectangle(15.0, VIRTUAL_HEIGHT),
+                    Placed,
+                ))
+                .id();
+            course_index.insert(obstacle, left, right);
+            obstacle
+        });
+        world.insert_resource(course_index);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(detect_collisions);
+
+        // act
+        schedule.run(&mut world);
+
+        // assert
+        let result: Vec<CollisionPair> = world
+            .resource_mut::<Messages<CollisionStarted>>()
+            .drain()
+            .map(|CollisionStarted { pair, .. }| pair)
+            .collect();
+        assert_eq!(result, [CollisionPair::new(flipper, beside)]);
+    }
+
+    #[test]
+    fn despawn_passed_entities_keeps_the_course_a_respawn_returns_to() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        world.resource_mut::<Camera>().left_displacement = 2_000.0;
+        world.get_mut::<RespawnPoint>(flipper).unwrap().x = 1_000.0;
+        let mut course_index = CourseIndex::default();
+        let [behind_respawn, behind_camera, ahead] = [500.0, 1_200.0, 3_000.0].map(|x| {
+            let position = Position { x, y: 0.0 };
+            let shape = ObstacleShape {
+                gap_length: 200.0,
+                gap_y_displacement: 150.0,
+                width: 15.0,
+            };
+            let (left, right) = shape.horizontal_extent(&position);
+            let obstacle = world.spawn((position, shape, Placed)).id();
+            course_index.insert(obstacle, left, right);
+            obstacle
+        });
+        let picked_up = world.spawn(Placed).id();
+        course_index.insert(picked_up, 300.0, 316.0);
+        world.despawn(picked_up);
+        world.insert_resource(course_index);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(despawn_passed_entities);
+
+        // act
+        schedule.run(&mut world);
+
+        // assert
+        let result =
+            [behind_respawn, behind_camera, ahead].map(|entity| world.get_entity(entity).is_ok());
+        assert_eq!(result, [false, true, true]);
+        assert_eq!(world.resource::<CourseIndex>().len(), 2);
+    }
+
+    #[test]
+    fn race_ending_leaves_saved_scores_alone() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        world.insert_resource(TwoPlayerSettings { enabled: true });
+        let player_two = local_player(2, &Controls::default());
+        let [_, colour] = Theme::default().flippers;
+        let rival = world.spawn(flipper_bundle(player_two, colour)).id();
+        world.entity_mut(flipper).remove::<Lives>();
+        for entity in [flipper, rival] {
+            world.get_mut::<Score>(entity).unwrap().value = 7;
+            world.write_message(FlipperHit {
+                flipper: entity,
+                cause: FlipperHitCause::Obstacle,
+            });
+        }
+        let mut schedule = Schedule::default();
+        schedule.add_systems(handle_flipper_hit);
+
+        // act
+        schedule.run(&mut world);
+
+        // assert
+        let level_count = world.resource::<Campaign>().levels.len();
+        assert_eq!(*world.resource::<Progress>(), Progress::new(level_count));
+    }
+
+    #[test]
+    fn flipper_entering_through_wall_is_hit_even_if_gap_is_reached_mid_crossing() {
+        // arrange
+        let (mut world, flipper) = run_world();
+        let obstacle_position = Position { x: 400.0, y: 0.0 };
+        let obstacle_collider = Collider::rectangle(15.0, VIRTUAL_HEIGHT);
+        let obstacle = world
+            .spawn((
+                obstacle_position.clone(),
+                ObstacleShape {
+                    gap_length: 200.0,
+                    gap_y_displacement: 150.0,
+                    width: 15.0,
+                },
+                ClearedBy::default(),
+            ))
+            .id();
+        // the hitbox enters the wall above the gap, is inside the gap half-way through the
+        // crossing, and is past the obstacle by the end of the frame
+        let previous = Position { x: 360.0, y: 120.0 };
+        let position = Position { x: 440.0, y: 200.0 };
+        let time = world
+            .get::<Collider>(flipper)
+            .unwrap()
+            .sweep(
+                &previous,
+                &position,
+                &obstacle_collider,
+                &obstacle_position,
+                &obstacle_position,
+            )
+            .unwrap();
+        world
+            .entity_mut(flipper)
+            .insert((PreviousPosition(previous), position));
+        world.write_message(CollisionStarted {
+            pair: CollisionPair::new(flipper, obstacle),
+            time,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(handle_obstacle_flipper_collision);
+
+        // act
+        schedule.run(&mut world);
+
+        // assert
+        assert_eq!(world.resource::<Messages<FlipperHit>>().len(), 1);
+        assert_eq!(world.get::<Score>(flipper).unwrap().obstacles_cleared, 0);
+    }
 
     #[test]
     fn flipper_obstacle_collision_successfully_reports_miss() {
@@ -594,6 +2715,7 @@ mod tests {
             &obstacle_shape,
             &obstacle_position,
             &flipper_shape,
+            &Hitbox { inset: 0.0 },
             &flipper_position,
         );
 
@@ -621,10 +2743,324 @@ mod tests {
             &obstacle_shape,
             &obstacle_position,
             &flipper_shape,
+            &Hitbox { inset: 0.0 },
             &flipper_position,
         );
 
         // assert
         assert_eq!(result, Some(FlipperObstacleCollision::Obstacle));
     }
+
+    #[test]
+    fn flipper_obstacle_collision_clears_gap_when_touching_gap_edges() {
+        // arrange
+        let obstacle_shape = ObstacleShape {
+            gap_length: 20.0,
+            gap_y_displacement: 150.0,
+            width: 15.0,
+        };
+        let obstacle_position = Position { x: 100.0, y: 0.0 };
+        let flipper_shape = RectangleShape {
+            width: 20.0,
+            height: 20.0,
+        };
+        let flipper_position = Position { x: 90.0, y: 150.0 };
+
+        // act
+        let result = obstacle_flipper_collision(
+            &obstacle_shape,
+            &obstacle_position,
+            &flipper_shape,
+            &Hitbox { inset: 0.0 },
+            &flipper_position,
+        );
+
+        // assert
+        assert_eq!(result, Some(FlipperObstacleCollision::Gap));
+    }
+
+    #[test]
+    fn flipper_obstacle_collision_ignores_touching_obstacle_side() {
+        // arrange
+        let obstacle_shape = ObstacleShape {
+            gap_length: 200.0,
+            gap_y_displacement: 150.0,
+            width: 15.0,
+        };
+        let obstacle_position = Position { x: 120.0, y: 0.0 };
+        let flipper_shape = RectangleShape {
+            width: 20.0,
+            height: 20.0,
+        };
+        let flipper_position = Position { x: 100.0, y: 50.0 };
+
+        // act
+        let result = obstacle_flipper_collision(
+            &obstacle_shape,
+            &obstacle_position,
+            &flipper_shape,
+            &Hitbox { inset: 0.0 },
+            &flipper_position,
+        );
+
+        // assert
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn flipper_obstacle_collision_forgives_graze_within_hitbox_inset() {
+        // arrange
+        let obstacle_shape = ObstacleShape {
+            gap_length: 100.0,
+            gap_y_displacement: 150.0,
+            width: 15.0,
+        };
+        let obstacle_position = Position { x: 100.0, y: 0.0 };
+        let flipper_shape = RectangleShape {
+            width: 20.0,
+            height: 20.0,
+        };
+        let flipper_position = Position { x: 90.0, y: 148.5 };
+
+        // act
+        let result = obstacle_flipper_collision(
+            &obstacle_shape,
+            &obstacle_position,
+            &flipper_shape,
+            &Hitbox { inset: 2.0 },
+            &flipper_position,
+        );
+
+        // assert
+        assert_eq!(result, Some(FlipperObstacleCollision::Gap));
+    }
+
+    #[test]
+    fn flipper_vertical_boundary_reports_expected_values() {
+        // arrange
+        let flipper_shape = RectangleShape {
+            width: 20.0,
+            height: 20.0,
+        };
+        let flipper_hitbox = Hitbox { inset: 2.0 };
+
+        // act
+        let result = [HUD_BAND_HEIGHT - 3.0, HUD_BAND_HEIGHT - 2.0, 598.0, 598.5].map(|y| {
+            flipper_vertical_boundary(&flipper_shape, &flipper_hitbox, &Position { x: 20.0, y })
+        });
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                Some(VerticalBoundary::Ceiling),
+                None,
+                None,
+                Some(VerticalBoundary::Floor)
+            ]
+        );
+    }
+
+    #[test]
+    fn last_player_out_breaks_ties_on_score() {
+        // arrange
+        let last_out = [(2, 3)];
+        let out_together = [(1, 4), (2, 6)];
+        let level_pegging = [(1, 5), (2, 5)];
+
+        // act
+        let result = [
+            last_player_out(&last_out),
+            last_player_out(&out_together),
+            last_player_out(&level_pegging),
+        ];
+
+        // assert
+        assert_eq!(result, [Some(2), Some(2), None]);
+    }
+
+    #[test]
+    fn predict_trajectory_falls_until_terminal_velocity() {
+        // arrange
+        let position = Position { x: 0.0, y: 100.0 };
+        let velocity = Velocity { x: 240.0, y: 18.0 };
+
+        // act
+        let result: Vec<(f32, f32)> = predict_trajectory(&position, &velocity, 12.0, 0.5, 3)
+            .iter()
+            .map(|Position { x, y }| (*x, *y))
+            .collect();
+
+        // assert
+        assert_eq!(result, [(120.0, 112.0), (240.0, 127.0), (360.0, 142.0)]);
+    }
+
+    /// Holds each flipper's flap for the frame, as `handle_flipper_controls` does while its key is
+    /// down.
+    #[allow(clippy::needless_pass_by_value)]
+    fn hold_flap(
+        mut query: Query<&mut Velocity, With<Flipper>>,
+        delta_time: Res<DeltaTime>,
+        difficulty: Res<Difficulty>,
+    ) {
+        for mut velocity in &mut query {
+            flap(
+                &mut velocity,
+                difficulty.flap_strength(),
+                delta_time.seconds,
+            );
+        }
+    }
+
+    #[test]
+    fn flipper_arc_keeps_its_shape_at_every_frame_rate_and_time_scale() {
+        // arrange
+        let cases = [(60.0, 1.0), (30.0, 1.0), (60.0, 0.5), (60.0, 0.25)];
+
+        // act
+        let result = cases.map(|(frames_per_second, time_scale): (f32, f32)| {
+            let (mut world, flipper) = run_world();
+            let mut flapping = Schedule::default();
+            flapping.add_systems((hold_flap, update_flipper_vertical_position).chain());
+            let mut gliding = Schedule::default();
+            gliding.add_systems(update_flipper_vertical_position);
+            let seconds = time_scale / frames_per_second;
+            world.insert_resource(DeltaTime {
+                seconds,
+                unscaled_seconds: 1.0 / frames_per_second,
+            });
+            let start = world.get::<Position>(flipper).unwrap().y;
+            // flap for a quarter of a second of game time then glide for another, however many
+            // frames that takes
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let frames = (0.25 * frames_per_second / time_scale).round() as usize;
+            for _ in 0..frames {
+                flapping.run(&mut world);
+            }
+            let released = world.get::<Position>(flipper).unwrap().clone();
+            let predicted = predict_trajectory(
+                &released,
+                world.get::<Velocity>(flipper).unwrap(),
+                Difficulty::default().gravity(),
+                seconds,
+                frames,
+            );
+            for _ in 0..frames {
+                gliding.run(&mut world);
+            }
+            (
+                world.get::<Position>(flipper).unwrap().y - start,
+                predicted.last().unwrap().y - start,
+            )
+        });
+
+        // assert
+        let (full_speed_rise, _) = result[0];
+        assert!(full_speed_rise < -40.0, "{full_speed_rise}");
+        for (rise, predicted_rise) in result {
+            // longer frames leave a couple of pixels of integration error, nothing more
+            assert!(
+                (rise - full_speed_rise).abs() < 3.0,
+                "{rise} vs {full_speed_rise}"
+            );
+            assert!((rise - predicted_rise).abs() < 0.01);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn flipper_obstacle_collision_reports_none_without_horizontal_overlap(
+            gap_length in 0.0f32..600.0,
+            gap_y_displacement in 0.0f32..600.0,
+            width in 1.0f32..100.0,
+            obstacle_x in -1_000.0f32..1_000.0,
+            flipper_y in -50.0f32..650.0,
+            inset in 0.0f32..5.0,
+            clearance in 0.0f32..500.0,
+        ) {
+            let obstacle_shape = ObstacleShape { gap_length, gap_y_displacement, width };
+            let obstacle_position = Position { x: obstacle_x, y: 0.0 };
+            let flipper_shape = RectangleShape { width: 20.0, height: 20.0 };
+            let flipper_hitbox = Hitbox { inset };
+            // hitbox right edge at or before the obstacle left edge
+            let flipper_position = Position { x: obstacle_x - 20.0 + inset - clearance, y: flipper_y };
+
+            let result = obstacle_flipper_collision(
+                &obstacle_shape,
+                &obstacle_position,
+                &flipper_shape,
+                &flipper_hitbox,
+                &flipper_position,
+            );
+
+            prop_assert_eq!(result, None);
+        }
+
+        #[test]
+        fn flipper_obstacle_collision_clears_gap_containing_hitbox(
+            gap_top in 0.0f32..500.0,
+            spare in 0.0f32..100.0,
+            offset in 0.0f32..=1.0,
+            width in 1.0f32..100.0,
+            overlap in 0.1f32..=1.0,
+            inset in 0.0f32..5.0,
+        ) {
+            let hitbox_height = 20.0 - 2.0 * inset;
+            let gap_length = hitbox_height + spare;
+            let obstacle_shape = ObstacleShape { gap_length, gap_y_displacement: gap_top, width };
+            let obstacle_position = Position { x: 500.0, y: 0.0 };
+            let flipper_shape = RectangleShape { width: 20.0, height: 20.0 };
+            let flipper_hitbox = Hitbox { inset };
+            // hitbox anywhere between touching the gap top and touching the gap bottom, and
+            // overlapping the obstacle horizontally
+            let flipper_position = Position {
+                x: 500.0 - 20.0 + inset + overlap * width,
+                y: gap_top + offset * spare - inset,
+            };
+
+            let result = obstacle_flipper_collision(
+                &obstacle_shape,
+                &obstacle_position,
+                &flipper_shape,
+                &flipper_hitbox,
+                &flipper_position,
+            );
+
+            prop_assert_eq!(result, Some(FlipperObstacleCollision::Gap));
+        }
+
+        #[test]
+        fn flipper_obstacle_collision_larger_inset_never_turns_gap_into_obstacle(
+            gap_length in 0.0f32..300.0,
+            gap_y_displacement in 0.0f32..600.0,
+            obstacle_x in 0.0f32..100.0,
+            flipper_y in -50.0f32..650.0,
+            inset in 0.0f32..4.0,
+            extra_inset in 0.0f32..4.0,
+        ) {
+            let obstacle_shape = ObstacleShape { gap_length, gap_y_displacement, width: 15.0 };
+            let obstacle_position = Position { x: obstacle_x, y: 0.0 };
+            let flipper_shape = RectangleShape { width: 20.0, height: 20.0 };
+            let flipper_position = Position { x: 50.0, y: flipper_y };
+
+            let result = obstacle_flipper_collision(
+                &obstacle_shape,
+                &obstacle_position,
+                &flipper_shape,
+                &Hitbox { inset },
+                &flipper_position,
+            );
+            let forgiving_result = obstacle_flipper_collision(
+                &obstacle_shape,
+                &obstacle_position,
+                &flipper_shape,
+                &Hitbox { inset: inset + extra_inset },
+                &flipper_position,
+            );
+
+            if result == Some(FlipperObstacleCollision::Gap) {
+                prop_assert_ne!(forgiving_result, Some(FlipperObstacleCollision::Obstacle));
+            }
+        }
+    }
 }
@@ -0,0 +1,304 @@
+use std::{borrow::Cow, collections::HashMap, fs};
+
+use bevy_ecs::resource::Resource;
+use macroquad::logging::{error, warn};
+
+use crate::{components::Colour, resources::GameMode, widget::Style};
+
+/// Colour palettes the game ships, picked in settings.  Each has a file in `assets/themes`, read
+/// when the palette is picked, with a copy built into the executable in case the file is
+/// missing.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Palette {
+    #[default]
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [
+        Palette::Standard,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::HighContrast,
+    ];
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        let index = Palette::ALL
+            .iter()
+            .position(|value| *value == self)
+            .unwrap_or_default();
+        Palette::ALL[(index + 1) % Palette::ALL.len()]
+    }
+
+    /// Id of the palette's name in the string table.
+    #[must_use]
+    pub fn message_id(self) -> &'static str {
+        match self {
+            Palette::Standard => "palette-standard",
+            Palette::Deuteranopia => "palette-deuteranopia",
+            Palette::Protanopia => "palette-protanopia",
+            Palette::HighContrast => "palette-high-contrast",
+        }
+    }
+
+    /// Name of the palette's file, also used in saved preferences.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Palette::Standard => "standard",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::HighContrast => "high-contrast",
+        }
+    }
+
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        Palette::ALL.into_iter().find(|value| value.code() == code)
+    }
+
+    fn built_in_source(self) -> &'static str {
+        match self {
+            Palette::Standard => include_str!("../assets/themes/standard.txt"),
+            Palette::Deuteranopia => include_str!("../assets/themes/deuteranopia.txt"),
+            Palette::Protanopia => include_str!("../assets/themes/protanopia.txt"),
+            Palette::HighContrast => include_str!("../assets/themes/high-contrast.txt"),
+        }
+    }
+
+    /// Palette from `assets/themes`, falling back to the built-in copy if the file cannot be
+    /// read.
+    fn source(self) -> Cow<'static, str> {
+        let path = format!("./assets/themes/{}.txt", self.code());
+        match fs::read_to_string(&path) {
+            Ok(value) => Cow::Owned(value),
+            Err(error) => {
+                warn!("Unable to read {path}, using the built-in palette: {error}");
+                Cow::Borrowed(self.built_in_source())
+            }
+        }
+    }
+}
+
+/// Parses `#rrggbb` into an opaque colour.
+fn parse_colour(value: &str) -> Option<Colour> {
+    let hex = value.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+
+    Some(Colour::new(channel(0)?, channel(2)?, channel(4)?, 255))
+}
+
+/// Parses `role = #rrggbb` lines, skipping blank lines and `#` comments.
+fn parse_palette(source: &str) -> HashMap<String, Colour> {
+    let mut result = HashMap::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let colour = line
+            .split_once('=')
+            .and_then(|(role, value)| Some((role.trim(), parse_colour(value.trim())?)));
+        let Some((role, colour)) = colour else {
+            error!(
+                "Ignoring palette line {} without a `role = #rrggbb` colour: `{line}`",
+                index + 1
+            );
+            continue;
+        };
+        result.insert(role.to_string(), colour);
+    }
+
+    result
+}
+
+/// Background, text and accent colours for one screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenColours {
+    pub background: Colour,
+    pub text: Colour,
+
+    /// Highlights: selected entries and the frame around a focused button.
+    pub accent: Colour,
+}
+
+/// Colours for each role on screen, from the chosen palette.
+#[derive(Debug, Resource)]
+pub struct Theme {
+    palette: Palette,
+    pub title: ScreenColours,
+    pub menu: ScreenColours,
+    pub playing: ScreenColours,
+    pub game_over: ScreenColours,
+    pub won: ScreenColours,
+
+    /// Quit confirmation, whose background dims the screen beneath.
+    pub exit: ScreenColours,
+
+    /// Fill behind panels.
+    pub panel: Colour,
+
+    /// Fill behind the HUD, across the band at the top of the screen kept clear of the course.
+    pub hud_band: Colour,
+    pub obstacle: Colour,
+    pub checkpoint: Colour,
+    pub finish_line: Colour,
+
+    /// Flipper colour for each player, in player order.
+    pub flippers: [Colour; 2],
+    pub trajectory: Colour,
+    pub pearl: Colour,
+    pub shell: Colour,
+    pub magnet: Colour,
+    pub shield: Colour,
+    pub slow_motion: Colour,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(Palette::default())
+    }
+}
+
+impl Theme {
+    /// Theme for `palette`, with any role the palette leaves out taking the standard colour.
+    #[must_use]
+    pub fn new(palette: Palette) -> Self {
+        let colours = parse_palette(&palette.source());
+        let standard = parse_palette(&Palette::Standard.source());
+        let colour = |role: &str| {
+            colours
+                .get(role)
+                .or_else(|| standard.get(role))
+                .copied()
+                .unwrap_or_else(|| {
+                    error!("No palette gives a colour for `{role}`.");
+                    Colour::new(255, 0, 255, 255)
+                })
+        };
+        let screen = |name: &str| ScreenColours {
+            background: colour(&format!("{name}-background")),
+            text: colour(&format!("{name}-text")),
+            accent: colour(&format!("{name}-accent")),
+        };
+
+        Theme {
+            palette,
+            title: screen("title"),
+            menu: screen("menu"),
+            playing: screen("playing"),
+            game_over: screen("game-over"),
+            won: screen("won"),
+            exit: screen("exit"),
+            panel: colour("panel"),
+            hud_band: colour("hud-band"),
+            obstacle: colour("obstacle"),
+            checkpoint: colour("checkpoint"),
+            finish_line: colour("finish-line"),
+            flippers: [colour("flipper-one"), colour("flipper-two")],
+            trajectory: colour("trajectory"),
+            pearl: colour("pearl"),
+            shell: colour("shell"),
+            magnet: colour("magnet"),
+            shield: colour("shield"),
+            slow_motion: colour("slow-motion"),
+        }
+    }
+
+    #[must_use]
+    pub fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// Colours for the screen shown in `mode`.
+    #[must_use]
+    pub fn screen(&self, mode: &GameMode) -> &ScreenColours {
+        match mode {
+            GameMode::Exiting(_) => &self.exit,
+            GameMode::Menu => &self.menu,
+            GameMode::Playing => &self.playing,
+            GameMode::GameOver => &self.game_over,
+            GameMode::Title => &self.title,
+            GameMode::Won => &self.won,
+        }
+    }
+
+    /// Widget style for text drawn over `screen`.
+    #[must_use]
+    pub fn style(&self, screen: &ScreenColours) -> Style {
+        Style {
+            text: screen.text,
+            accent: screen.accent,
+            panel: self.panel,
+            text_scale: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Palette, Theme, parse_colour, parse_palette};
+    use crate::components::Colour;
+
+    #[test]
+    fn parse_palette_reads_hex_colours_and_skips_bad_lines() {
+        // arrange
+        let source = "# comment\nobstacle = #4cb944\n\nshell = coral\nno colour here\n";
+
+        // act
+        let result = parse_palette(source);
+
+        // assert
+        assert_eq!(result.len(), 1);
+        assert_eq!(result["obstacle"], Colour::new(76, 185, 68, 255));
+        assert_eq!(parse_colour("#12345"), None);
+    }
+
+    #[test]
+    fn every_palette_gives_every_standard_role() {
+        // arrange
+        let standard = parse_palette(&Palette::Standard.source());
+
+        // act
+        let palettes: Vec<_> = Palette::ALL
+            .iter()
+            .map(|palette| (*palette, parse_palette(&palette.source())))
+            .collect();
+
+        // assert
+        for (palette, colours) in palettes {
+            let mut missing: Vec<&String> = standard
+                .keys()
+                .filter(|role| !colours.contains_key(*role))
+                .collect();
+            missing.sort();
+            assert!(missing.is_empty(), "{palette:?} is missing {missing:?}");
+        }
+    }
+
+    #[test]
+    fn colour_blind_palettes_tell_players_apart_from_obstacles() {
+        // arrange
+        let palettes = [
+            Palette::Deuteranopia,
+            Palette::Protanopia,
+            Palette::HighContrast,
+        ];
+
+        // act
+        let themes = palettes.map(Theme::new);
+
+        // assert
+        for theme in themes {
+            let [one, two] = theme.flippers;
+            assert_ne!(one, two);
+            assert_ne!(one, theme.obstacle);
+            assert_ne!(two, theme.obstacle);
+            assert_ne!(theme.obstacle, theme.playing.background);
+        }
+    }
+}
@@ -1,278 +1,1160 @@
-use crate::{WINDOW_WIDTH, components::Colour};
-use macroquad::text::{Font, TextDimensions, TextParams, draw_text_ex, measure_text};
-
-pub const COLUMBIABLUE: Colour = Colour {
-    r: 189,
-    g: 213,
-    b: 234,
-    a: 255,
+use crate::{
+    components::{Colour, Combo, Player, Score, TimeTrial},
+    controls::{Action, Controls, GamepadButton, InputDevice},
+    daily::DailyChallenge,
+    display::{HUD_BAND_HEIGHT, VIRTUAL_HEIGHT, VIRTUAL_WIDTH},
+    level::{Medal, MedalTimes},
+    locale::Strings,
+    menu::MenuItem,
+    progress::LevelStatus,
+    resources::{Difficulty, GameFonts, PlayMode},
+    theme::{Palette, Theme},
+    widget::{
+        Align, Anchor, Button, Face, Panel, ProgressBar, Stack, Style, Text, TextRole, Tone,
+        Widget, draw_widget,
+    },
 };
+use macroquad::{input::KeyCode, math::Rect, shapes::draw_rectangle};
+use std::fmt::Display;
 
-pub const DARKPASTELGREEN: Colour = Colour {
-    r: 76,
-    g: 185,
-    b: 68,
-    a: 255,
-};
+/// Name of `key` in the locale, for prompts.  Letter and function keys go by their own names.
+fn key_name(key: KeyCode, strings: &Strings) -> String {
+    let id = match key {
+        KeyCode::Space => "key-space",
+        KeyCode::Enter => "key-enter",
+        KeyCode::Escape => "key-escape",
+        KeyCode::Backspace => "key-backspace",
+        KeyCode::Up => "key-up",
+        KeyCode::Down => "key-down",
+        KeyCode::Left => "key-left",
+        KeyCode::Right => "key-right",
+        KeyCode::RightShift => "key-right-shift",
+        _ => return format!("{key:?}").to_uppercase(),
+    };
 
-pub const DEEPSKYBLUE: Colour = Colour {
-    r: 0,
-    g: 187,
-    b: 249,
-    a: 255,
-};
+    strings.get(id).to_string()
+}
 
-pub const DRABDARKBROWN: Colour = Colour {
-    r: 48,
-    g: 50,
-    b: 28,
-    a: 255,
-};
+/// Name of gamepad `button` in the locale, for prompts.
+fn button_name(button: GamepadButton, strings: &Strings) -> String {
+    let id = match button {
+        GamepadButton::South => "button-south",
+        GamepadButton::East => "button-east",
+        GamepadButton::North => "button-north",
+        GamepadButton::West => "button-west",
+        GamepadButton::Start => "button-start",
+        GamepadButton::Select => "button-select",
+        GamepadButton::DPadUp => "button-up",
+        GamepadButton::DPadDown => "button-down",
+        GamepadButton::DPadLeft => "button-left",
+        GamepadButton::DPadRight => "button-right",
+    };
 
-pub const MAIZE: Colour = Colour {
-    r: 254,
-    g: 228,
-    b: 64,
-    a: 255,
-};
+    strings.get(id).to_string()
+}
 
-pub const YINMNBLUE: Colour = Colour {
-    r: 39,
-    g: 76,
-    b: 119,
-    a: 255,
-};
+/// What to press for `action` on the device in use: a click when using the mouse and the
+/// action takes one, the first button bound to it when using a gamepad, otherwise the first key
+/// bound to it.
+fn prompt(action: Action, controls: &Controls, strings: &Strings) -> String {
+    match controls.device {
+        InputDevice::Mouse if action.takes_click() => {
+            return strings.get("mouse-click").to_string();
+        }
+        InputDevice::Gamepad => {
+            if let Some(button) = controls.buttons(action).first() {
+                return button_name(*button, strings);
+            }
+        }
+        _ => {}
+    }
+    controls
+        .keys(action)
+        .first()
+        .map_or_else(|| String::from("?"), |key| key_name(*key, strings))
+}
 
-pub fn draw_exit_screen_text(body_font: &Font) {
-    let body_text_0 = "Leave the game?";
-    let TextDimensions {
-        width: body_text_width_0,
-        ..
-    } = measure_text(body_text_0, Some(body_font), 36, 1.0);
-    draw_text_ex(
-        body_text_0,
-        0.5 * (WINDOW_WIDTH - body_text_width_0),
-        155.0,
-        TextParams {
-            font_size: 36,
-            font: Some(body_font),
-            color: YINMNBLUE.into(),
-            ..Default::default()
-        },
-    );
+/// Message with each placeholder in `actions` filled by the prompt for its action.
+fn prompt_text(
+    id: &str,
+    actions: &[(&str, Action)],
+    controls: &Controls,
+    strings: &Strings,
+) -> String {
+    let prompts: Vec<(&str, String)> = actions
+        .iter()
+        .map(|(name, action)| (*name, prompt(*action, controls, strings)))
+        .collect();
+    let arguments: Vec<(&str, &dyn Display)> = prompts
+        .iter()
+        .map(|(name, value)| (*name, value as &dyn Display))
+        .collect();
 
-    let body_text_1 = "Press ENTER to confirm,";
-    let TextDimensions {
-        width: body_text_width_1,
-        ..
-    } = measure_text(body_text_1, Some(body_font), 36, 1.0);
-    draw_text_ex(
-        body_text_1,
-        0.5 * (WINDOW_WIDTH - body_text_width_1),
-        350.0,
-        TextParams {
-            font_size: 36,
-            font: Some(body_font),
-            color: YINMNBLUE.into(),
-            ..Default::default()
-        },
-    );
+    strings.format(id, &arguments)
+}
 
-    let body_text_1 = "Esc to go back.";
-    let TextDimensions {
-        width: body_text_width_1,
-        ..
-    } = measure_text(body_text_1, Some(body_font), 36, 1.0);
-    draw_text_ex(
-        body_text_1,
-        0.5 * (WINDOW_WIDTH - body_text_width_1),
-        425.0,
-        TextParams {
-            font_size: 36,
-            font: Some(body_font),
-            color: YINMNBLUE.into(),
-            ..Default::default()
-        },
-    );
+fn score_breakdown_text(score: &Score, strings: &Strings) -> String {
+    let Score {
+        value,
+        obstacles_cleared,
+        items_collected,
+    } = score;
+    strings.format(
+        "score-breakdown",
+        &[
+            ("score", value),
+            ("cleared", obstacles_cleared),
+            ("collected", items_collected),
+        ],
+    )
 }
 
-pub fn draw_game_over_screen_text(body_font: &Font) {
-    let body_text_0 = "Game over!";
-    let TextDimensions {
-        width: body_text_width_0,
-        ..
-    } = measure_text(body_text_0, Some(body_font), 36, 1.0);
-    draw_text_ex(
-        body_text_0,
-        0.5 * (WINDOW_WIDTH - body_text_width_0),
-        155.0,
-        TextParams {
-            font_size: 36,
-            font: Some(body_font),
-            color: YINMNBLUE.into(),
-            ..Default::default()
-        },
-    );
+/// Level select entry for one campaign level.
+pub struct LevelSummary<'a> {
+    pub name: &'a str,
+    pub status: LevelStatus,
+    pub best_score: Option<u32>,
+}
 
-    let body_text_1 = "Press SPACE to play again.";
-    let TextDimensions {
-        width: body_text_width_1,
-        ..
-    } = measure_text(body_text_1, Some(body_font), 48, 1.0);
-    draw_text_ex(
-        body_text_1,
-        0.5 * (WINDOW_WIDTH - body_text_width_1),
-        300.0,
-        TextParams {
-            font_size: 48,
-            font: Some(body_font),
-            color: YINMNBLUE.into(),
-            ..Default::default()
-        },
-    );
+fn level_summary_text(position: usize, summary: &LevelSummary, strings: &Strings) -> String {
+    let LevelSummary {
+        name,
+        status,
+        best_score,
+    } = summary;
+    let status = strings.get(match status {
+        LevelStatus::Locked => "level-status-locked",
+        LevelStatus::Unlocked => "level-status-unlocked",
+        LevelStatus::Completed => "level-status-completed",
+    });
+    match best_score {
+        Some(value) => strings.format(
+            "level-summary-best",
+            &[
+                ("position", &position),
+                ("name", name),
+                ("status", &status),
+                ("best", value),
+            ],
+        ),
+        None => strings.format(
+            "level-summary",
+            &[("position", &position), ("name", name), ("status", &status)],
+        ),
+    }
+}
+
+/// Options picked on the settings page, ahead of a run.
+pub struct MenuSettings<'a> {
+    pub play_mode: PlayMode,
+    pub difficulty: Difficulty,
+    pub lives_enabled: bool,
+    pub two_player: bool,
+
+    /// Practice start and time scale, shown when practice is picked.
+    pub practice: Option<String>,
+
+    /// Name of the locale on show, in its own language.
+    pub language: &'a str,
+    pub palette: Palette,
+}
+
+/// What the menu page on show displays, besides its items.
+pub enum MenuView<'a> {
+    Main,
+    LevelSelect {
+        levels: &'a [LevelSummary<'a>],
+        selected: usize,
+
+        /// Shown in place of the level list when the daily challenge is picked.
+        daily: Option<[String; 2]>,
+    },
+    Settings(MenuSettings<'a>),
+    HighScores(Vec<String>),
+    Credits,
+}
+
+fn best_score_text(best_score: Option<u32>) -> String {
+    best_score.map_or_else(|| String::from("-"), |value| value.to_string())
+}
+
+/// Best score on each difficulty for one level, for the high scores page.
+#[must_use]
+pub fn high_score_text(name: &str, best_scores: [Option<u32>; 3], strings: &Strings) -> String {
+    std::iter::once(name.to_string())
+        .chain(
+            Difficulty::ALL
+                .iter()
+                .zip(best_scores)
+                .map(|(difficulty, best_score)| {
+                    strings.format(
+                        "high-score-entry",
+                        &[
+                            ("difficulty", &strings.get(difficulty.message_id())),
+                            ("best", &best_score_text(best_score)),
+                        ],
+                    )
+                }),
+        )
+        .collect::<Vec<_>>()
+        .join("    ")
 }
 
-pub fn draw_info_text(score: u32, body_font: &Font) {
-    let info_text = if score == 0 {
-        String::from("Press SPACE to flap your fins.")
+#[must_use]
+pub fn practice_settings_text(
+    start_obstacle: usize,
+    time_scale: f32,
+    controls: &Controls,
+    strings: &Strings,
+) -> String {
+    let start = if start_obstacle == 0 {
+        strings.get("practice-start-level").to_string()
     } else {
-        format!("Score: {score}")
+        strings.format("practice-start-obstacle", &[("obstacle", &start_obstacle)])
     };
-    draw_text_ex(
-        &info_text,
-        20.0,
-        40.0,
-        TextParams {
-            font_size: 24,
-            font: Some(body_font),
-            color: DRABDARKBROWN.into(),
-            ..Default::default()
-        },
+    let percent = (100.0 * time_scale).round();
+    let [earlier, later, speed] = [
+        Action::PracticeEarlier,
+        Action::PracticeLater,
+        Action::PracticeSpeed,
+    ]
+    .map(|action| prompt(action, controls, strings));
+
+    strings.format(
+        "practice-settings",
+        &[
+            ("earlier", &earlier),
+            ("later", &later),
+            ("start", &start),
+            ("speed", &speed),
+            ("percent", &percent),
+        ],
+    )
+}
+
+/// Date, seed and difficulty of the daily challenge, for comparing results out of band, then
+/// today's local attempts and best score at that difficulty.
+#[must_use]
+pub fn daily_challenge_text(
+    daily_challenge: &DailyChallenge,
+    difficulty: Difficulty,
+    strings: &Strings,
+) -> [String; 2] {
+    let DailyChallenge { date, attempts, .. } = daily_challenge;
+
+    [
+        strings.format(
+            "daily-challenge",
+            &[
+                ("date", date),
+                ("seed", &daily_challenge.seed()),
+                ("difficulty", &strings.get(difficulty.message_id())),
+            ],
+        ),
+        strings.format(
+            "daily-attempts",
+            &[
+                ("attempts", attempts),
+                (
+                    "best",
+                    &best_score_text(daily_challenge.best_score(difficulty)),
+                ),
+            ],
+        ),
+    ]
+}
+
+/// Live time trial clock, the latest split, and how long is left for the next medal.
+fn time_trial_text(time_trial: &TimeTrial, medal_times: &MedalTimes, strings: &Strings) -> String {
+    let TimeTrial { elapsed, splits } = time_trial;
+    let mut parts = vec![strings.format("time-trial-clock", &[("time", &format!("{elapsed:.2}"))])];
+    if let Some(split) = splits.last() {
+        parts.push(strings.format(
+            "time-trial-split",
+            &[("split", &splits.len()), ("time", &format!("{split:.2}"))],
+        ));
+    }
+    parts.push(match medal_times.next_target(*elapsed) {
+        Some((medal, threshold)) => strings.format(
+            "time-trial-target",
+            &[
+                ("medal", &strings.get(medal.message_id())),
+                ("remaining", &format!("{:.1}", threshold - elapsed)),
+            ],
+        ),
+        None => strings.get("time-trial-no-medal").to_string(),
+    });
+
+    parts.join("    ")
+}
+
+fn time_trial_result_text(time: f32, medal: Option<Medal>, strings: &Strings) -> String {
+    let time = format!("{time:.2}");
+    match medal {
+        Some(value) => strings.format(
+            "time-trial-result",
+            &[("time", &time), ("medal", &strings.get(value.message_id()))],
+        ),
+        None => strings.format("time-trial-result-no-medal", &[("time", &time)]),
+    }
+}
+
+/// `power_ups` pairs each active power-up's message id with its seconds remaining.
+fn power_up_text(power_ups: &[(&str, f32)], strings: &Strings) -> Vec<String> {
+    power_ups
+        .iter()
+        .map(|(id, remaining)| {
+            strings.format(
+                "power-up-timer",
+                &[
+                    ("power-up", &strings.get(id)),
+                    ("remaining", &format!("{remaining:.1}")),
+                ],
+            )
+        })
+        .collect()
+}
+
+/// Dims the screen beneath an overlay.
+pub fn draw_overlay_veil(theme: &Theme) {
+    let veil = Colour {
+        a: 170,
+        ..theme.exit.background
+    };
+    draw_rectangle(0.0, 0.0, VIRTUAL_WIDTH, VIRTUAL_HEIGHT, veil.into());
+}
+
+pub fn draw_exit_screen_text(
+    controls: &Controls,
+    strings: &Strings,
+    theme: &Theme,
+    fonts: &GameFonts,
+) {
+    let dialogue = Panel::new(
+        Stack::new(Align::Centre)
+            .spacing(20.0)
+            .child(Text::new(strings.get("exit-heading"), TextRole::Title))
+            .child(Widget::Spacer(100.0))
+            .child(Text::new(
+                prompt_text(
+                    "exit-confirm",
+                    &[("confirm", Action::Confirm)],
+                    controls,
+                    strings,
+                ),
+                TextRole::Title,
+            ))
+            .child(Text::new(
+                prompt_text("exit-back", &[("back", Action::Back)], controls, strings),
+                TextRole::Title,
+            )),
+    )
+    .padding(40.0)
+    .min_width(560.0);
+    draw_widget(
+        &dialogue.into(),
+        Anchor::CENTRE,
+        &theme.style(&theme.exit),
+        fonts,
     );
 }
 
-pub fn draw_menu_screen_text(body_font: &Font) {
-    let body_text_0 = "Are you ready?";
-    let TextDimensions {
-        width: body_text_width_0,
-        ..
-    } = measure_text(body_text_0, Some(body_font), 36, 1.0);
-    draw_text_ex(
-        body_text_0,
-        0.5 * (WINDOW_WIDTH - body_text_width_0),
-        175.0,
-        TextParams {
-            font_size: 36,
-            font: Some(body_font),
-            color: DRABDARKBROWN.into(),
-            ..Default::default()
-        },
+pub fn draw_game_over_screen_text(
+    score: &Score,
+    daily: Option<&[String; 2]>,
+    controls: &Controls,
+    strings: &Strings,
+    theme: &Theme,
+    fonts: &GameFonts,
+) {
+    let replay = prompt_text(
+        "game-over-replay",
+        &[("continue", Action::Continue)],
+        controls,
+        strings,
     );
+    let screen = Stack::new(Align::Centre)
+        .spacing(20.0)
+        .child(Text::new(strings.get("game-over-heading"), TextRole::Title))
+        .child(Text::new(
+            score_breakdown_text(score, strings),
+            TextRole::Body,
+        ))
+        .child(Widget::Spacer(20.0))
+        .child(Button::new(replay, TextRole::Prompt).focused(true))
+        .children(
+            daily
+                .into_iter()
+                .flatten()
+                .map(|line| Text::new(line, TextRole::Caption)),
+        );
+    draw_widget(
+        &screen.into(),
+        Anchor::CENTRE,
+        &theme.style(&theme.game_over),
+        fonts,
+    );
+}
+
+const HUD_LINE_SPACING: f32 = 4.0;
+const HUD_TEXT_SCALE: f32 = 0.75;
+const PROGRESS_BAR_WIDTH: f32 = 240.0;
+const PROGRESS_BAR_HEIGHT: f32 = 8.0;
+
+/// State of a single-player run, as the HUD shows it.
+pub struct HudStatus<'a> {
+    pub score: &'a Score,
+    pub lives: Option<u32>,
+
+    /// Saved best score to beat, if this run counts towards one.
+    pub best_score: Option<u32>,
+    pub combo: &'a Combo,
+
+    /// Metres travelled from the start, and from the start to the finish line.
+    pub distance: f32,
+    pub course_length: f32,
+    pub time_trial: Option<(&'a TimeTrial, &'a MedalTimes)>,
+
+    /// Each active power-up's message id with its seconds remaining.
+    pub power_ups: &'a [(&'a str, f32)],
+}
 
-    let body_text_1 = "Press SPACE to play.";
-    let TextDimensions {
-        width: body_text_width_1,
+/// HUD for a single-player run: a bar showing progress to the finish line above lines of
+/// text, all within the HUD band.
+pub fn draw_info_text(
+    status: &HudStatus,
+    controls: &Controls,
+    strings: &Strings,
+    theme: &Theme,
+    fonts: &GameFonts,
+) {
+    let HudStatus {
+        score,
+        lives,
+        time_trial,
+        power_ups,
         ..
-    } = measure_text(body_text_1, Some(body_font), 48, 1.0);
-    draw_text_ex(
-        body_text_1,
-        0.5 * (WINDOW_WIDTH - body_text_width_1),
-        350.0,
-        TextParams {
-            font_size: 48,
-            font: Some(body_font),
-            color: DRABDARKBROWN.into(),
-            ..Default::default()
-        },
+    } = status;
+    let Score {
+        value,
+        obstacles_cleared,
+        items_collected,
+    } = score;
+    let info_text = if *value == 0 && time_trial.is_some() {
+        prompt_text(
+            "hud-flap-hint-time-trial",
+            &[
+                ("flap", Action::Flap),
+                ("brake", Action::Brake),
+                ("boost", Action::Boost),
+            ],
+            controls,
+            strings,
+        )
+    } else if *value == 0 {
+        prompt_text(
+            "hud-flap-hint",
+            &[("flap", Action::Flap)],
+            controls,
+            strings,
+        )
+    } else {
+        strings.format(
+            "hud-score",
+            &[
+                ("score", value),
+                ("cleared", obstacles_cleared),
+                ("collected", items_collected),
+            ],
+        )
+    };
+    let info_text = match lives {
+        Some(remaining) => format!(
+            "{}    {info_text}",
+            strings.format("hud-lives", &[("lives", remaining)])
+        ),
+        None => info_text,
+    };
+
+    // time trial and power-ups share the last line, keeping the HUD inside its band
+    let timers: Vec<String> = time_trial
+        .map(|(time_trial, medal_times)| time_trial_text(time_trial, medal_times, strings))
+        .into_iter()
+        .chain(power_up_text(power_ups, strings))
+        .collect();
+    let mut hud_lines = vec![info_text, run_status_text(status, strings)];
+    if !timers.is_empty() {
+        hud_lines.push(timers.join("    "));
+    }
+    let progress = ProgressBar::new(
+        status.distance / status.course_length,
+        PROGRESS_BAR_WIDTH,
+        PROGRESS_BAR_HEIGHT,
     );
+    let hud = Stack::new(Align::Start)
+        .spacing(HUD_LINE_SPACING)
+        .child(progress)
+        .children(
+            hud_lines
+                .into_iter()
+                .map(|line| Text::new(line, TextRole::Caption)),
+        );
+    draw_hud_band(theme);
+    draw_widget(&hud.into(), Anchor::TOP_LEFT, &hud_style(theme), fonts);
 }
 
-pub fn draw_title_screen_text(heading_font: &Font, body_font: &Font, body_italic_font: &Font) {
-    let heading_text = "Flipper";
-    let TextDimensions {
-        width: heading_width,
-        ..
-    } = measure_text(heading_text, Some(heading_font), 144, 1.0);
-    draw_text_ex(
-        heading_text,
-        0.5 * (WINDOW_WIDTH - heading_width),
-        250.0,
-        TextParams {
-            font_size: 144,
-            font: Some(heading_font),
-            color: YINMNBLUE.into(),
-            rotation: -0.06,
-            ..Default::default()
-        },
+/// Fills the band across the top of the screen the course leaves clear for the HUD.
+fn draw_hud_band(theme: &Theme) {
+    draw_rectangle(
+        0.0,
+        0.0,
+        VIRTUAL_WIDTH,
+        HUD_BAND_HEIGHT,
+        theme.hud_band.into(),
     );
+}
 
-    let subheading_text = "the gentle giant manta ray";
-    let TextDimensions {
-        width: subheading_width,
-        ..
-    } = measure_text(subheading_text, Some(body_italic_font), 36, 1.0);
-    draw_text_ex(
-        subheading_text,
-        0.5 * (WINDOW_WIDTH - subheading_width),
-        400.0,
-        TextParams {
-            font_size: 36,
-            font: Some(body_italic_font),
-            color: YINMNBLUE.into(),
-            ..Default::default()
-        },
+/// Smaller text than the rest of the game, so the HUD fits in its band.
+fn hud_style(theme: &Theme) -> Style {
+    Style {
+        text_scale: HUD_TEXT_SCALE,
+        ..theme.style(&theme.playing)
+    }
+}
+
+/// Distance along the course, the best score to beat and any combo running.
+fn run_status_text(status: &HudStatus, strings: &Strings) -> String {
+    let mut parts = vec![strings.format(
+        "hud-distance",
+        &[
+            ("distance", &format!("{:.0}", status.distance)),
+            ("length", &format!("{:.0}", status.course_length)),
+        ],
+    )];
+    if let Some(best) = status.best_score {
+        parts.push(strings.format("hud-best", &[("best", &best)]));
+    }
+    if status.combo.streak > 0 {
+        parts.push(strings.format(
+            "hud-combo",
+            &[
+                ("multiplier", &status.combo.multiplier()),
+                ("streak", &status.combo.streak),
+            ],
+        ));
+    }
+
+    parts.join("    ")
+}
+
+/// Lines of HUD text, down the top left of the screen.
+fn draw_hud_lines(lines: impl IntoIterator<Item = String>, theme: &Theme, fonts: &GameFonts) {
+    let hud = Stack::new(Align::Start).spacing(HUD_LINE_SPACING).children(
+        lines
+            .into_iter()
+            .map(|line| Text::new(line, TextRole::Caption)),
     );
+    draw_hud_band(theme);
+    draw_widget(&hud.into(), Anchor::TOP_LEFT, &hud_style(theme), fonts);
+}
 
-    let body_text = "game loading...";
-    let TextDimensions {
-        width: body_text_width,
-        ..
-    } = measure_text(body_text, Some(body_font), 28, 1.0);
-    draw_text_ex(
-        body_text,
-        0.5 * (WINDOW_WIDTH - body_text_width),
-        550.0,
-        TextParams {
-            font_size: 28,
-            font: Some(body_font),
-            color: YINMNBLUE.into(),
-            ..Default::default()
-        },
+fn menu_item_label(item: MenuItem, view: &MenuView, strings: &Strings) -> String {
+    match (item, view) {
+        (
+            MenuItem::Level(index),
+            MenuView::LevelSelect {
+                levels, selected, ..
+            },
+        ) => {
+            let level_text = levels
+                .get(index)
+                .map(|summary| level_summary_text(index + 1, summary, strings))
+                .unwrap_or_default();
+            if index == *selected {
+                format!("> {level_text} <")
+            } else {
+                level_text
+            }
+        }
+        (MenuItem::PlayMode, MenuView::Settings(settings)) => strings.format(
+            "setting-mode",
+            &[("mode", &strings.get(settings.play_mode.message_id()))],
+        ),
+        (MenuItem::Difficulty, MenuView::Settings(settings)) => strings.format(
+            "setting-difficulty",
+            &[("difficulty", &strings.get(settings.difficulty.message_id()))],
+        ),
+        (MenuItem::Lives, MenuView::Settings(settings)) => {
+            let lives = if settings.lives_enabled {
+                "setting-on"
+            } else {
+                "setting-off"
+            };
+            strings.format("setting-lives", &[("lives", &strings.get(lives))])
+        }
+        (MenuItem::Players, MenuView::Settings(settings)) => {
+            let players = if settings.two_player { 2 } else { 1 };
+            strings.format("setting-players", &[("players", &players)])
+        }
+        (MenuItem::Language, MenuView::Settings(settings)) => {
+            strings.format("setting-language", &[("language", &settings.language)])
+        }
+        (MenuItem::Palette, MenuView::Settings(settings)) => strings.format(
+            "setting-palette",
+            &[("palette", &strings.get(settings.palette.message_id()))],
+        ),
+        (item, _) => strings
+            .get(match item {
+                MenuItem::Play => "menu-play",
+                MenuItem::LevelSelect => "menu-level-select",
+                MenuItem::Settings => "menu-settings",
+                MenuItem::HighScores => "menu-high-scores",
+                MenuItem::Credits => "menu-credits",
+                MenuItem::Quit => "menu-quit",
+                MenuItem::PracticeStart => "setting-practice-start",
+                MenuItem::PracticeSpeed => "setting-practice-speed",
+                MenuItem::Back => "menu-back",
+                _ => "",
+            })
+            .to_string(),
+    }
+}
+
+/// Draws a menu page, its `items` as buttons with the one at `focus` highlighted, returning
+/// the area each item covers, in item order.
+#[must_use]
+pub fn draw_menu_screen_text(
+    view: &MenuView,
+    items: &[MenuItem],
+    focus: usize,
+    controls: &Controls,
+    strings: &Strings,
+    theme: &Theme,
+    fonts: &GameFonts,
+) -> Vec<Rect> {
+    let heading = match view {
+        MenuView::Main => {
+            Text::new(strings.get("game-title"), TextRole::Banner).face(Face::Heading)
+        }
+        MenuView::LevelSelect { daily: Some(_), .. } => {
+            Text::new(strings.get("daily-heading"), TextRole::Title)
+        }
+        MenuView::LevelSelect { daily: None, .. } => {
+            Text::new(strings.get("level-select-heading"), TextRole::Title)
+        }
+        MenuView::Settings(_) => Text::new(strings.get("settings-heading"), TextRole::Title),
+        MenuView::HighScores(_) => Text::new(strings.get("high-scores-heading"), TextRole::Title),
+        MenuView::Credits => Text::new(strings.get("credits-heading"), TextRole::Title),
+    };
+    let (body, notes): (Vec<String>, Vec<String>) = match view {
+        MenuView::Main | MenuView::LevelSelect { daily: None, .. } => (Vec::new(), Vec::new()),
+        MenuView::LevelSelect {
+            daily: Some(lines), ..
+        } => (lines.to_vec(), Vec::new()),
+        MenuView::Settings(MenuSettings { practice, .. }) => (
+            Vec::new(),
+            std::iter::once(prompt_text(
+                "settings-shortcuts",
+                &[
+                    ("mode", Action::Mode),
+                    ("difficulty", Action::Difficulty),
+                    ("lives", Action::Lives),
+                    ("players", Action::Players),
+                ],
+                controls,
+                strings,
+            ))
+            .chain(practice.iter().cloned())
+            .collect(),
+        ),
+        MenuView::HighScores(lines) => (lines.clone(), Vec::new()),
+        MenuView::Credits => (
+            ["credits-author", "credits-fonts", "credits-engine"]
+                .map(|id| strings.get(id).to_string())
+                .to_vec(),
+            Vec::new(),
+        ),
+    };
+    let navigation_hint = if controls.device == InputDevice::Mouse {
+        prompt_text(
+            "menu-navigation-hint-pointer",
+            &[("back", Action::Back)],
+            controls,
+            strings,
+        )
+    } else {
+        prompt_text(
+            "menu-navigation-hint",
+            &[
+                ("up", Action::Up),
+                ("down", Action::Down),
+                ("confirm", Action::Confirm),
+                ("back", Action::Back),
+            ],
+            controls,
+            strings,
+        )
+    };
+
+    let screen = Stack::new(Align::Centre)
+        .spacing(10.0)
+        .child(heading)
+        .children(
+            body.into_iter()
+                .map(|line| Text::new(line, TextRole::Body).tone(Tone::Accent)),
+        )
+        .child(Widget::Spacer(10.0))
+        .children(items.iter().enumerate().map(|(index, item)| {
+            Button::new(menu_item_label(*item, view, strings), TextRole::Body)
+                .focused(index == focus)
+        }))
+        .child(Widget::Spacer(10.0))
+        .children(
+            notes
+                .into_iter()
+                .chain(std::iter::once(navigation_hint))
+                .map(|line| Text::new(line, TextRole::Caption)),
+        );
+    draw_widget(
+        &screen.into(),
+        Anchor::CENTRE,
+        &theme.style(&theme.menu),
+        fonts,
+    )
+}
+
+pub fn draw_title_screen_text(strings: &Strings, theme: &Theme, fonts: &GameFonts) {
+    let screen = Stack::new(Align::Centre)
+        .child(
+            Text::new(strings.get("game-title"), TextRole::Heading)
+                .face(Face::Heading)
+                .rotation(-0.06),
+        )
+        .child(Widget::Spacer(60.0))
+        .child(Text::new(strings.get("title-subheading"), TextRole::Title).face(Face::BodyItalic))
+        .child(Widget::Spacer(110.0))
+        .child(Text::new(strings.get("title-loading"), TextRole::Body));
+    draw_widget(
+        &screen.into(),
+        Anchor::CENTRE,
+        &theme.style(&theme.title),
+        fonts,
     );
 }
 
-pub fn draw_win_screen_text(body_font: &Font) {
-    let body_text_0 = "*** You won! ***";
-    let TextDimensions {
-        width: body_text_width_0,
-        ..
-    } = measure_text(body_text_0, Some(body_font), 72, 1.0);
-    draw_text_ex(
-        body_text_0,
-        0.5 * (WINDOW_WIDTH - body_text_width_0),
-        155.0,
-        TextParams {
-            font_size: 72,
-            font: Some(body_font),
-            color: COLUMBIABLUE.into(),
-            ..Default::default()
-        },
+pub fn draw_win_screen_text(
+    score: &Score,
+    time_trial_result: Option<(f32, Option<Medal>)>,
+    daily: Option<&[String; 2]>,
+    controls: &Controls,
+    strings: &Strings,
+    theme: &Theme,
+    fonts: &GameFonts,
+) {
+    let screen = Stack::new(Align::Centre)
+        .spacing(20.0)
+        .child(Text::new(strings.get("win-heading"), TextRole::Banner))
+        .child(Text::new(
+            score_breakdown_text(score, strings),
+            TextRole::Body,
+        ))
+        .children(time_trial_result.map(|(time, medal)| {
+            Text::new(time_trial_result_text(time, medal, strings), TextRole::Body)
+        }))
+        .child(Widget::Spacer(10.0))
+        .child(Button::new(continue_text(controls, strings), TextRole::Title).focused(true))
+        .children(
+            daily
+                .into_iter()
+                .flatten()
+                .map(|line| Text::new(line, TextRole::Caption)),
+        );
+    draw_widget(
+        &screen.into(),
+        Anchor::CENTRE,
+        &theme.style(&theme.won),
+        fonts,
     );
+}
 
-    let body_text_1 = "Press SPACE to play again.";
-    let TextDimensions {
-        width: body_text_width_1,
-        ..
-    } = measure_text(body_text_1, Some(body_font), 36, 1.0);
-    draw_text_ex(
-        body_text_1,
-        0.5 * (WINDOW_WIDTH - body_text_width_1),
-        350.0,
-        TextParams {
-            font_size: 36,
-            font: Some(body_font),
-            color: COLUMBIABLUE.into(),
-            ..Default::default()
-        },
+fn continue_text(controls: &Controls, strings: &Strings) -> String {
+    prompt_text(
+        "continue-prompt",
+        &[("continue", Action::Continue)],
+        controls,
+        strings,
+    )
+}
+
+/// One player's line of the two-player HUD.
+#[must_use]
+pub fn player_info_text(
+    player: Player,
+    score: &Score,
+    lives: Option<u32>,
+    eliminated: bool,
+    power_ups: &[(&str, f32)],
+    strings: &Strings,
+) -> String {
+    let mut parts = vec![strings.format(
+        "player-info",
+        &[
+            ("number", &player.number),
+            ("key", &key_name(player.flap_key, strings)),
+            ("score", &score.value),
+        ],
+    )];
+    if let Some(remaining) = lives {
+        parts.push(strings.format("hud-lives", &[("lives", &remaining)]));
+    }
+    if eliminated {
+        parts.push(strings.get("player-out").to_string());
+    }
+    parts.extend(power_up_text(power_ups, strings));
+
+    parts.join("    ")
+}
+
+/// HUD for a two-player race: a line per player, then the time trial clock when racing against
+/// it.
+pub fn draw_players_info_text(
+    player_lines: &[String],
+    time_trial: Option<(&TimeTrial, &MedalTimes)>,
+    strings: &Strings,
+    theme: &Theme,
+    fonts: &GameFonts,
+) {
+    let time_trial_line = time_trial
+        .map(|(time_trial, medal_times)| time_trial_text(time_trial, medal_times, strings));
+    draw_hud_lines(
+        player_lines.iter().cloned().chain(time_trial_line),
+        theme,
+        fonts,
     );
 }
+
+/// Heading naming the winner of a two-player race, then each player's score.
+#[must_use]
+pub fn race_results_text(
+    results: &[(Player, &Score)],
+    winner: Option<u8>,
+    strings: &Strings,
+) -> Vec<String> {
+    let heading = match winner {
+        Some(number) => strings.format("race-winner", &[("number", &number)]),
+        None => strings.get("race-draw").to_string(),
+    };
+
+    std::iter::once(heading)
+        .chain(results.iter().map(|(player, score)| {
+            strings.format(
+                "race-score",
+                &[
+                    ("number", &player.number),
+                    ("breakdown", &score_breakdown_text(score, strings)),
+                ],
+            )
+        }))
+        .collect()
+}
+
+/// Results screen closing a two-player race, whether it ended at the finish line or with both
+/// players out.
+pub fn draw_race_results_text(
+    lines: &[String],
+    style: &Style,
+    controls: &Controls,
+    strings: &Strings,
+    fonts: &GameFonts,
+) {
+    let mut screen = Stack::new(Align::Centre).spacing(15.0);
+    if let Some((heading, scores)) = lines.split_first() {
+        screen = screen
+            .child(Text::new(heading, TextRole::Prompt))
+            .children(scores.iter().map(|line| Text::new(line, TextRole::Body)));
+    }
+    let screen = screen
+        .child(Widget::Spacer(40.0))
+        .child(Button::new(continue_text(controls, strings), TextRole::Title).focused(true));
+    draw_widget(&screen.into(), Anchor::CENTRE, style, fonts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        HudStatus, LevelSummary, high_score_text, level_summary_text, player_info_text,
+        practice_settings_text, prompt_text, race_results_text, run_status_text,
+        time_trial_result_text, time_trial_text,
+    };
+    use crate::{
+        components::{Combo, Player, Score, TimeTrial},
+        controls::{Action, ButtonEvent, Controls, GamepadButton, InputDevice},
+        level::{Medal, MedalTimes},
+        locale::{Locale, Strings},
+        progress::LevelStatus,
+    };
+    use macroquad::input::KeyCode;
+
+    #[test]
+    fn run_status_text_shows_distance_best_and_running_combo() {
+        // arrange
+        let score = Score::default();
+        let status = |best_score, combo| HudStatus {
+            score: &score,
+            lives: None,
+            best_score,
+            combo,
+            distance: 12.4,
+            course_length: 100.0,
+            time_trial: None,
+            power_ups: &[],
+        };
+        let strings = Strings::new(Locale::English);
+
+        // act
+        let result = [
+            run_status_text(&status(Some(9), &Combo { streak: 6 }), &strings),
+            run_status_text(&status(None, &Combo::default()), &strings),
+        ];
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                "12 m / 100 m    Best: 9    Combo x2 (6 in a row)",
+                "12 m / 100 m",
+            ]
+        );
+    }
+
+    #[test]
+    fn time_trial_text_shows_latest_split_and_next_medal() {
+        // arrange
+        let time_trial = TimeTrial {
+            elapsed: 11.0,
+            splits: vec![2.5, 5.25],
+        };
+        let medal_times = MedalTimes {
+            gold: 10.0,
+            silver: 12.5,
+            bronze: 14.0,
+        };
+
+        // act
+        let result = time_trial_text(&time_trial, &medal_times, &Strings::new(Locale::English));
+
+        // assert
+        assert_eq!(
+            result,
+            "Time: 11.00s    Split 2: 5.25s    Silver: 1.5s left"
+        );
+    }
+
+    #[test]
+    fn player_info_text_shows_key_lives_and_elimination() {
+        // arrange
+        let player = Player {
+            number: 2,
+            flap_key: KeyCode::RightShift,
+        };
+        let score = Score {
+            value: 7,
+            obstacles_cleared: 5,
+            items_collected: 1,
+        };
+
+        // act
+        let result = player_info_text(
+            player,
+            &score,
+            Some(1),
+            true,
+            &[("power-up-shield", 2.5)],
+            &Strings::new(Locale::English),
+        );
+
+        // assert
+        assert_eq!(
+            result,
+            "Player 2 (RIGHT SHIFT): 7    Lives: 1    Out    Shield 2.5s"
+        );
+    }
+
+    #[test]
+    fn race_results_text_names_winner_or_draw() {
+        // arrange
+        let players = [
+            Player {
+                number: 1,
+                flap_key: KeyCode::Space,
+            },
+            Player {
+                number: 2,
+                flap_key: KeyCode::Enter,
+            },
+        ];
+        let scores = [
+            Score {
+                value: 4,
+                obstacles_cleared: 4,
+                items_collected: 0,
+            },
+            Score {
+                value: 6,
+                obstacles_cleared: 5,
+                items_collected: 1,
+            },
+        ];
+        let results = [(players[0], &scores[0]), (players[1], &scores[1])];
+
+        // act
+        let strings = Strings::new(Locale::English);
+        let result = race_results_text(&results, Some(2), &strings);
+        let draw = race_results_text(&results, None, &strings);
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                "Player 2 wins!",
+                "Player 1: Score 4: 4 cleared, 0 collected",
+                "Player 2: Score 6: 5 cleared, 1 collected"
+            ]
+        );
+        assert_eq!(draw[0], "It's a draw!");
+    }
+
+    #[test]
+    fn practice_settings_text_shows_start_and_speed() {
+        // arrange
+        let start_obstacle = 3;
+
+        // act
+        let controls = Controls::default();
+        let strings = Strings::new(Locale::English);
+        let result = [
+            practice_settings_text(0, 1.0, &controls, &strings),
+            practice_settings_text(start_obstacle, 0.75, &controls, &strings),
+        ];
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                "LEFT/RIGHT start: level start    S speed: 100%",
+                "LEFT/RIGHT start: obstacle 3    S speed: 75%"
+            ]
+        );
+    }
+
+    #[test]
+    fn prompt_text_follows_active_device() {
+        // arrange
+        let mut controls = Controls::default();
+        let strings = Strings::new(Locale::English);
+        let keyboard = prompt_text(
+            "continue-prompt",
+            &[("continue", Action::Continue)],
+            &controls,
+            &strings,
+        );
+
+        // act
+        controls.device = InputDevice::Mouse;
+        let mouse = [
+            prompt_text(
+                "continue-prompt",
+                &[("continue", Action::Continue)],
+                &controls,
+                &strings,
+            ),
+            prompt_text("exit-back", &[("back", Action::Back)], &controls, &strings),
+        ];
+
+        // assert
+        assert_eq!(keyboard, "Press SPACE to continue.");
+        assert_eq!(mouse, ["Press CLICK to continue.", "Esc to go back."]);
+    }
+
+    #[test]
+    fn prompt_text_shows_gamepad_buttons_falling_back_to_keys() {
+        // arrange
+        let mut controls = Controls::default();
+        controls.update_buttons([ButtonEvent::Pressed(GamepadButton::Start)]);
+        let strings = Strings::new(Locale::English);
+
+        // act
+        let result = [
+            prompt_text("exit-back", &[("back", Action::Back)], &controls, &strings),
+            prompt_text(
+                "hud-flap-hint",
+                &[("flap", Action::Flap)],
+                &controls,
+                &strings,
+            ),
+        ];
+
+        // assert
+        assert_eq!(
+            result,
+            ["(B) to go back.", "Press SPACE to flap your fins."]
+        );
+    }
+
+    #[test]
+    fn time_trial_result_text_names_medal() {
+        // arrange
+        let time = 9.876;
+
+        // act
+        let result =
+            time_trial_result_text(time, Some(Medal::Gold), &Strings::new(Locale::English));
+
+        // assert
+        assert_eq!(result, "Time 9.88s - Gold medal");
+    }
+
+    #[test]
+    fn high_score_text_lists_best_score_per_difficulty() {
+        // arrange
+        let best_scores = [Some(12), None, Some(30)];
+
+        // act
+        let result = high_score_text("Shallows", best_scores, &Strings::new(Locale::English));
+
+        // assert
+        assert_eq!(result, "Shallows    Easy 12    Normal -    Hard 30");
+    }
+
+    #[test]
+    fn level_summary_text_includes_best_score_when_set() {
+        // arrange
+        let summary = LevelSummary {
+            name: "Shallows",
+            status: LevelStatus::Completed,
+            best_score: Some(12),
+        };
+
+        // act
+        let result = level_summary_text(1, &summary, &Strings::new(Locale::English));
+
+        // assert
+        assert_eq!(result, "1. Shallows - completed - best 12");
+    }
+
+    #[test]
+    fn level_summary_text_omits_missing_best_score() {
+        // arrange
+        let summary = LevelSummary {
+            name: "The Trench",
+            status: LevelStatus::Locked,
+            best_score: None,
+        };
+
+        // act
+        let result = level_summary_text(3, &summary, &Strings::new(Locale::English));
+
+        // assert
+        assert_eq!(result, "3. The Trench - locked");
+    }
+}
@@ -0,0 +1,682 @@
+use macroquad::{
+    math::{Rect, Vec2, vec2},
+    shapes::{draw_rectangle, draw_rectangle_lines},
+    text::{Font, TextParams, draw_text_ex, measure_text},
+};
+
+use crate::{
+    components::Colour,
+    display::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH},
+    resources::GameFonts,
+};
+
+/// Gap kept between an anchored widget tree and the edge of the screen.
+const SCREEN_MARGIN: f32 = 20.0;
+
+/// Space between a button's label and its frame.
+const BUTTON_PADDING: Vec2 = vec2(16.0, 8.0);
+
+/// Thickness of the frame around a focused button.
+const BUTTON_FRAME: f32 = 3.0;
+
+/// Share of a line's height above the baseline, which macroquad draws text from.
+const ASCENT: f32 = 0.75;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Start,
+    Centre,
+}
+
+impl Align {
+    /// Offset which aligns something `size` long within `available`.
+    fn offset(self, size: f32, available: f32) -> f32 {
+        match self {
+            Align::Start => 0.0,
+            Align::Centre => 0.5 * (available - size),
+        }
+    }
+}
+
+/// Where a widget tree sits on screen: its horizontal, then vertical, alignment within the
+/// screen margin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anchor(pub Align, pub Align);
+
+impl Anchor {
+    pub const CENTRE: Anchor = Anchor(Align::Centre, Align::Centre);
+    pub const TOP_LEFT: Anchor = Anchor(Align::Start, Align::Start);
+
+    fn place(self, size: Vec2, container: Rect) -> Rect {
+        let Anchor(horizontal, vertical) = self;
+        Rect::new(
+            container.x + horizontal.offset(size.x, container.w),
+            container.y + vertical.offset(size.y, container.h),
+            size.x,
+            size.y,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Face {
+    Body,
+    BodyItalic,
+    Heading,
+}
+
+impl Face {
+    /// Loaded font for this face; text falls back to macroquad's built-in font until it loads.
+    /// The locale's script font, when there is one, stands in for every face.
+    fn font(self, fonts: &GameFonts) -> Option<&Font> {
+        if let Some(value) = &fonts.script {
+            return Some(value);
+        }
+        match self {
+            Face::Body => fonts.body.as_ref(),
+            Face::BodyItalic => fonts.body_italic.as_ref(),
+            Face::Heading => fonts.heading.as_ref(),
+        }
+    }
+}
+
+/// Kind of text, which the style maps to a font size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextRole {
+    Caption,
+    Body,
+    Title,
+    Prompt,
+    Banner,
+    Heading,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tone {
+    Text,
+    Accent,
+}
+
+/// Colours and font sizes a widget tree is drawn with, so widgets name a role rather than a
+/// colour or size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Style {
+    pub text: Colour,
+
+    /// Highlights: selected entries and the frame around a focused button.
+    pub accent: Colour,
+
+    /// Fill behind panels.
+    pub panel: Colour,
+
+    /// Multiplies every font size.
+    pub text_scale: f32,
+}
+
+impl Style {
+    #[must_use]
+    pub fn font_size(&self, role: TextRole) -> u16 {
+        let base = match role {
+            TextRole::Caption => 24.0,
+            TextRole::Body => 28.0,
+            TextRole::Title => 36.0,
+            TextRole::Prompt => 48.0,
+            TextRole::Banner => 72.0,
+            TextRole::Heading => 144.0,
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let result = (base * self.text_scale).round() as u16;
+        result
+    }
+
+    fn colour(&self, tone: Tone) -> Colour {
+        match tone {
+            Tone::Text => self.text,
+            Tone::Accent => self.accent,
+        }
+    }
+}
+
+/// A single line of text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Text {
+    content: String,
+    role: TextRole,
+    face: Face,
+    tone: Tone,
+    rotation: f32,
+}
+
+impl Text {
+    pub fn new(content: impl Into<String>, role: TextRole) -> Self {
+        Text {
+            content: content.into(),
+            role,
+            face: Face::Body,
+            tone: Tone::Text,
+            rotation: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub fn face(mut self, face: Face) -> Self {
+        self.face = face;
+        self
+    }
+
+    #[must_use]
+    pub fn tone(mut self, tone: Tone) -> Self {
+        self.tone = tone;
+        self
+    }
+
+    /// Rotation in radians, about the start of the baseline.  Layout ignores it.
+    #[must_use]
+    pub fn rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    fn size(&self, style: &Style, measure: &Measure) -> Vec2 {
+        let font_size = style.font_size(self.role);
+        vec2(
+            measure(&self.content, self.face, font_size),
+            f32::from(font_size),
+        )
+    }
+
+    fn primitive(&self, position: Vec2, tone: Tone, style: &Style) -> Primitive<'_> {
+        let font_size = style.font_size(self.role);
+        Primitive::Text {
+            text: self,
+            x: position.x,
+            baseline: position.y + ASCENT * f32::from(font_size),
+            font_size,
+            colour: style.colour(tone),
+        }
+    }
+}
+
+/// Labelled action; a focused button is framed and labelled in the style's accent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Button {
+    label: Text,
+    focused: bool,
+}
+
+impl Button {
+    pub fn new(label: impl Into<String>, role: TextRole) -> Self {
+        Button {
+            label: Text::new(label, role),
+            focused: false,
+        }
+    }
+
+    #[must_use]
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+}
+
+/// Filled box, padded around its child.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Panel {
+    child: Box<Widget>,
+    padding: f32,
+    min_width: f32,
+}
+
+impl Panel {
+    pub fn new(child: impl Into<Widget>) -> Self {
+        Panel {
+            child: Box::new(child.into()),
+            padding: 20.0,
+            min_width: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    #[must_use]
+    pub fn min_width(mut self, min_width: f32) -> Self {
+        self.min_width = min_width;
+        self
+    }
+}
+
+/// Bar filled in the style's accent to a fraction of its width, over a track in the panel
+/// colour.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressBar {
+    fraction: f32,
+    size: Vec2,
+}
+
+impl ProgressBar {
+    #[must_use]
+    pub fn new(fraction: f32, width: f32, height: f32) -> Self {
+        ProgressBar {
+            fraction: fraction.clamp(0.0, 1.0),
+            size: vec2(width, height),
+        }
+    }
+}
+
+/// Children laid out top to bottom, `spacing` apart, each aligned across the stack's width.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stack {
+    children: Vec<Widget>,
+    spacing: f32,
+    align: Align,
+}
+
+impl Stack {
+    #[must_use]
+    pub fn new(align: Align) -> Self {
+        Stack {
+            children: Vec::new(),
+            spacing: 0.0,
+            align,
+        }
+    }
+
+    #[must_use]
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    #[must_use]
+    pub fn child(mut self, child: impl Into<Widget>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    #[must_use]
+    pub fn children<W: Into<Widget>>(mut self, children: impl IntoIterator<Item = W>) -> Self {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// Node of a declared screen.  Screens build a fresh tree each frame, which gets laid out
+/// against the virtual screen and drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Widget {
+    Button(Button),
+    Panel(Panel),
+    ProgressBar(ProgressBar),
+
+    /// Empty vertical space of the given height.
+    Spacer(f32),
+    Stack(Stack),
+    Text(Text),
+}
+
+impl From<Button> for Widget {
+    fn from(button: Button) -> Self {
+        Widget::Button(button)
+    }
+}
+
+impl From<Panel> for Widget {
+    fn from(panel: Panel) -> Self {
+        Widget::Panel(panel)
+    }
+}
+
+impl From<ProgressBar> for Widget {
+    fn from(bar: ProgressBar) -> Self {
+        Widget::ProgressBar(bar)
+    }
+}
+
+impl From<Stack> for Widget {
+    fn from(stack: Stack) -> Self {
+        Widget::Stack(stack)
+    }
+}
+
+impl From<Text> for Widget {
+    fn from(text: Text) -> Self {
+        Widget::Text(text)
+    }
+}
+
+/// Width of a line of text in a face and font size.
+type Measure<'a> = dyn Fn(&str, Face, u16) -> f32 + 'a;
+
+/// Drawing command produced by laying out a widget tree.
+#[derive(Debug, PartialEq)]
+enum Primitive<'w> {
+    Fill {
+        rect: Rect,
+        colour: Colour,
+    },
+    Frame {
+        rect: Rect,
+        colour: Colour,
+    },
+    Text {
+        text: &'w Text,
+        x: f32,
+        baseline: f32,
+        font_size: u16,
+        colour: Colour,
+    },
+
+    /// Area a button covers, for hit testing; draws nothing.
+    Target {
+        rect: Rect,
+    },
+}
+
+impl Widget {
+    fn size(&self, style: &Style, measure: &Measure) -> Vec2 {
+        match self {
+            Widget::Button(Button { label, .. }) => {
+                label.size(style, measure) + 2.0 * BUTTON_PADDING
+            }
+            Widget::Panel(Panel {
+                child,
+                padding,
+                min_width,
+            }) => {
+                let size = child.size(style, measure) + Vec2::splat(2.0 * padding);
+                vec2(size.x.max(*min_width), size.y)
+            }
+            Widget::ProgressBar(ProgressBar { size, .. }) => *size,
+            Widget::Spacer(height) => vec2(0.0, *height),
+            Widget::Stack(Stack {
+                children, spacing, ..
+            }) => {
+                let gaps = children.len().saturating_sub(1);
+                #[allow(clippy::cast_precision_loss)]
+                let initial = vec2(0.0, *spacing * gaps as f32);
+                children.iter().fold(initial, |accumulator, child| {
+                    let size = child.size(style, measure);
+                    vec2(accumulator.x.max(size.x), accumulator.y + size.y)
+                })
+            }
+            Widget::Text(text) => text.size(style, measure),
+        }
+    }
+
+    /// Appends the primitives drawing this widget within `rect`.
+    fn layout<'w>(
+        &'w self,
+        rect: Rect,
+        style: &Style,
+        measure: &Measure,
+        primitives: &mut Vec<Primitive<'w>>,
+    ) {
+        match self {
+            Widget::Button(Button { label, focused }) => {
+                let tone = if *focused {
+                    primitives.push(Primitive::Frame {
+                        rect,
+                        colour: style.accent,
+                    });
+                    Tone::Accent
+                } else {
+                    label.tone
+                };
+                primitives.push(label.primitive(rect.point() + BUTTON_PADDING, tone, style));
+                primitives.push(Primitive::Target { rect });
+            }
+            Widget::Panel(Panel { child, padding, .. }) => {
+                primitives.push(Primitive::Fill {
+                    rect,
+                    colour: style.panel,
+                });
+                let inner = Rect::new(
+                    rect.x + padding,
+                    rect.y + padding,
+                    rect.w - 2.0 * padding,
+                    rect.h - 2.0 * padding,
+                );
+                child.layout(inner, style, measure, primitives);
+            }
+            Widget::ProgressBar(ProgressBar { fraction, .. }) => {
+                primitives.push(Primitive::Fill {
+                    rect,
+                    colour: style.panel,
+                });
+                primitives.push(Primitive::Fill {
+                    rect: Rect::new(rect.x, rect.y, rect.w * fraction, rect.h),
+                    colour: style.accent,
+                });
+            }
+            Widget::Spacer(_) => {}
+            Widget::Stack(Stack {
+                children,
+                spacing,
+                align,
+            }) => {
+                let mut y = rect.y;
+                for child in children {
+                    let size = child.size(style, measure);
+                    let x = rect.x + align.offset(size.x, rect.w);
+                    child.layout(Rect::new(x, y, size.x, size.y), style, measure, primitives);
+                    y += size.y + spacing;
+                }
+            }
+            Widget::Text(text) => primitives.push(text.primitive(rect.point(), text.tone, style)),
+        }
+    }
+}
+
+/// Places a widget tree at `anchor` within the screen margin, returning what to draw.
+fn layout<'w>(
+    root: &'w Widget,
+    anchor: Anchor,
+    style: &Style,
+    measure: &Measure,
+) -> Vec<Primitive<'w>> {
+    let screen = Rect::new(
+        SCREEN_MARGIN,
+        SCREEN_MARGIN,
+        VIRTUAL_WIDTH - 2.0 * SCREEN_MARGIN,
+        VIRTUAL_HEIGHT - 2.0 * SCREEN_MARGIN,
+    );
+    let rect = anchor.place(root.size(style, measure), screen);
+    let mut result = Vec::new();
+    root.layout(rect, style, measure, &mut result);
+
+    result
+}
+
+/// Lays out a widget tree at `anchor` on the virtual screen and draws it, returning the area
+/// each button covers, in tree order.  Screens without buttons draw and ignore the areas.
+#[allow(clippy::must_use_candidate)]
+pub fn draw_widget(root: &Widget, anchor: Anchor, style: &Style, fonts: &GameFonts) -> Vec<Rect> {
+    let measure = |text: &str, face: Face, font_size: u16| {
+        measure_text(text, face.font(fonts), font_size, 1.0).width
+    };
+    let mut result = Vec::new();
+    for primitive in layout(root, anchor, style, &measure) {
+        match primitive {
+            Primitive::Fill { rect, colour } => {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, colour.into());
+            }
+            Primitive::Frame { rect, colour } => {
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, BUTTON_FRAME, colour.into());
+            }
+            Primitive::Text {
+                text,
+                x,
+                baseline,
+                font_size,
+                colour,
+            } => {
+                draw_text_ex(
+                    &text.content,
+                    x,
+                    baseline,
+                    TextParams {
+                        font_size,
+                        font: text.face.font(fonts),
+                        color: colour.into(),
+                        rotation: text.rotation,
+                        ..Default::default()
+                    },
+                );
+            }
+            Primitive::Target { rect } => result.push(rect),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Align, Anchor, Button, Face, Panel, Primitive, ProgressBar, Stack, Style, Text, TextRole,
+        Widget, layout,
+    };
+    use crate::components::Colour;
+    use macroquad::math::Rect;
+
+    const STYLE: Style = Style {
+        text: Colour {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        },
+        accent: Colour {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        },
+        panel: Colour {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        },
+        text_scale: 1.0,
+    };
+
+    /// Every character is half as wide as the font size.
+    #[allow(clippy::cast_precision_loss)]
+    fn measure(text: &str, _face: Face, font_size: u16) -> f32 {
+        0.5 * text.len() as f32 * f32::from(font_size)
+    }
+
+    fn text_positions(primitives: &[Primitive]) -> Vec<(f32, f32)> {
+        primitives
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Text { x, baseline, .. } => Some((*x, *baseline)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stack_centres_children_across_widest_line() {
+        // arrange
+        let root = Widget::from(
+            Stack::new(Align::Centre)
+                .spacing(10.0)
+                .child(Text::new("abcdefgh", TextRole::Caption))
+                .child(Text::new("abcd", TextRole::Caption)),
+        );
+
+        // act
+        let result = layout(&root, Anchor::TOP_LEFT, &STYLE, &measure);
+
+        // assert
+        assert_eq!(text_positions(&result), [(20.0, 38.0), (44.0, 72.0)]);
+    }
+
+    #[test]
+    fn centre_anchor_places_tree_in_middle_of_screen() {
+        // arrange
+        let root = Widget::from(Text::new("abcd", TextRole::Title));
+
+        // act
+        let result = layout(&root, Anchor::CENTRE, &STYLE, &measure);
+
+        // assert
+        assert_eq!(text_positions(&result), [(364.0, 309.0)]);
+    }
+
+    #[test]
+    fn panel_pads_child_and_stretches_to_min_width() {
+        // arrange
+        let root = Widget::from(
+            Panel::new(Text::new("ab", TextRole::Caption))
+                .padding(10.0)
+                .min_width(200.0),
+        );
+
+        // act
+        let result = layout(&root, Anchor::TOP_LEFT, &STYLE, &measure);
+
+        // assert
+        assert_eq!(
+            result[0],
+            Primitive::Fill {
+                rect: Rect::new(20.0, 20.0, 200.0, 44.0),
+                colour: STYLE.panel,
+            }
+        );
+        assert_eq!(text_positions(&result), [(30.0, 48.0)]);
+    }
+
+    #[test]
+    fn focused_button_is_framed_and_labelled_in_accent() {
+        // arrange
+        let root = Widget::from(Button::new("go", TextRole::Caption).focused(true));
+
+        // act
+        let result = layout(&root, Anchor::TOP_LEFT, &STYLE, &measure);
+
+        // assert
+        assert_eq!(
+            result[0],
+            Primitive::Frame {
+                rect: Rect::new(20.0, 20.0, 56.0, 40.0),
+                colour: STYLE.accent,
+            }
+        );
+        assert!(matches!(
+            result[1],
+            Primitive::Text { x: 36.0, baseline: 46.0, colour, .. } if colour == STYLE.accent
+        ));
+    }
+
+    #[test]
+    fn progress_bar_fills_fraction_of_track_in_accent() {
+        // arrange
+        let root = Widget::from(ProgressBar::new(1.5, 200.0, 8.0));
+
+        // act
+        let result = layout(&root, Anchor::TOP_LEFT, &STYLE, &measure);
+
+        // assert
+        assert_eq!(
+            result,
+            [
+                Primitive::Fill {
+                    rect: Rect::new(20.0, 20.0, 200.0, 8.0),
+                    colour: STYLE.panel,
+                },
+                Primitive::Fill {
+                    rect: Rect::new(20.0, 20.0, 200.0, 8.0),
+                    colour: STYLE.accent,
+                },
+            ]
+        );
+    }
+}